@@ -0,0 +1,106 @@
+#ifndef MNEMONIC_H
+#define MNEMONIC_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Length of seed written by `mnemonic_to_seed`
+#define MNEMONIC_SEED_LEN 64
+
+// Error codes returned by the C interface
+typedef enum MnemonicError {
+  MNEMONIC_ERROR_OK = 0,
+  // Required pointer is NULL
+  MNEMONIC_ERROR_NULL_POINTER = 1,
+  // String is not valid UTF-8
+  MNEMONIC_ERROR_INVALID_UTF8 = 2,
+  // Entropy does not have 16, 20, 24, 28 or 32 bytes
+  MNEMONIC_ERROR_INVALID_ENTROPY = 3,
+  // Mnemonic does not have 12, 15, 18, 21 or 24 words
+  MNEMONIC_ERROR_INVALID_WORD_COUNT = 4,
+  // Mnemonic contains word which is not in word list
+  MNEMONIC_ERROR_INVALID_WORD = 5,
+  // Checksum of mnemonic does not match
+  MNEMONIC_ERROR_INVALID_CHECKSUM = 6,
+  // Output buffer is too small, the required length was written
+  MNEMONIC_ERROR_BUFFER_TOO_SMALL = 7,
+} MnemonicError;
+
+// Encode entropy as mnemonic
+//
+// The mnemonic is written with terminating NUL, `mnemonic_len` receives its length without NUL. When the buffer is
+// too small, `mnemonic_len` receives the required capacity including NUL instead.
+//
+// # Safety
+//
+// `entropy` has to be valid for reads of `entropy_len` bytes, `mnemonic` valid for writes of `mnemonic_capacity`
+// bytes and `mnemonic_len` NULL or valid for writes.
+int32_t mnemonic_from_entropy(const uint8_t *entropy,
+                              size_t entropy_len,
+                              char *mnemonic,
+                              size_t mnemonic_capacity,
+                              size_t *mnemonic_len);
+
+// Decode entropy of mnemonic, words may be separated by any whitespace and use any case
+//
+// # Safety
+//
+// `mnemonic` has to be NUL terminated string, `entropy` valid for writes of `entropy_capacity` bytes and
+// `entropy_len` NULL or valid for writes.
+int32_t mnemonic_to_entropy(const char *mnemonic,
+                            uint8_t *entropy,
+                            size_t entropy_capacity,
+                            size_t *entropy_len);
+
+// Derive 64 bytes of BIP-39 seed from mnemonic
+//
+// The checksum of the mnemonic is verified first, since seeds of mistyped mnemonics hold no funds.
+//
+// # Safety
+//
+// `mnemonic` has to be NUL terminated string, `passphrase` NULL or NUL terminated string and `seed` valid for
+// writes of `seed_capacity` bytes.
+int32_t mnemonic_to_seed(const char *mnemonic,
+                         const char *passphrase,
+                         uint8_t *seed,
+                         size_t seed_capacity);
+
+// Check that mnemonic has valid words and checksum
+//
+// # Safety
+//
+// `mnemonic` has to be NUL terminated string.
+int32_t mnemonic_validate(const char *mnemonic);
+
+// Normalize whitespace and case of mnemonic, the result has to be released by `mnemonic_free`
+//
+// Returns NULL when the mnemonic is NULL or not valid UTF-8, the words are not checked.
+//
+// # Safety
+//
+// `mnemonic` has to be NULL or NUL terminated string.
+char *mnemonic_normalize(const char *mnemonic);
+
+// Zeroize and release string returned by `mnemonic_normalize`
+//
+// # Safety
+//
+// `mnemonic` has to be NULL or returned by `mnemonic_normalize` and not freed yet.
+void mnemonic_free(char *mnemonic);
+
+// Overwrite buffer of the caller with zeros, e.g. seed which is no longer needed
+//
+// # Safety
+//
+// `buffer` has to be NULL or valid for writes of `len` bytes.
+void mnemonic_zeroize(uint8_t *buffer, size_t len);
+
+// Static description of error code, never freed
+const char *mnemonic_error_message(int32_t code);
+
+#endif  /* MNEMONIC_H */
@@ -0,0 +1,327 @@
+//! C interface of mnemonic encoding and seed derivation, see `include/mnemonic.h`
+//!
+//! Results are written into buffers provided by the caller, strings are NUL terminated UTF-8. Every function returns
+//! `MnemonicError` as integer, lengths of results are returned also when the buffer is too small, so the caller can
+//! retry. Intermediate secrets are zeroized, `mnemonic_zeroize` clears buffers of the caller and `mnemonic_free`
+//! releases strings allocated by the library.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// Error codes returned by the C interface
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MnemonicError {
+    Ok = 0,
+    /// Required pointer is NULL
+    NullPointer = 1,
+    /// String is not valid UTF-8
+    InvalidUtf8 = 2,
+    /// Entropy does not have 16, 20, 24, 28 or 32 bytes
+    InvalidEntropy = 3,
+    /// Mnemonic does not have 12, 15, 18, 21 or 24 words
+    InvalidWordCount = 4,
+    /// Mnemonic contains word which is not in word list
+    InvalidWord = 5,
+    /// Checksum of mnemonic does not match
+    InvalidChecksum = 6,
+    /// Output buffer is too small, the required length was written
+    BufferTooSmall = 7,
+}
+
+impl MnemonicError {
+    const ALL: [MnemonicError; 8] = [
+        MnemonicError::Ok,
+        MnemonicError::NullPointer,
+        MnemonicError::InvalidUtf8,
+        MnemonicError::InvalidEntropy,
+        MnemonicError::InvalidWordCount,
+        MnemonicError::InvalidWord,
+        MnemonicError::InvalidChecksum,
+        MnemonicError::BufferTooSmall,
+    ];
+
+    /// Error of the integer code, None for unknown codes
+    fn of_code(code: i32) -> Option<MnemonicError> {
+        MnemonicError::ALL.iter().copied().find(|error| *error as i32 == code)
+    }
+
+    /// Static description with terminating NUL
+    fn message(self) -> &'static [u8] {
+        match self {
+            MnemonicError::Ok => b"OK\0",
+            MnemonicError::NullPointer => b"Required pointer is NULL\0",
+            MnemonicError::InvalidUtf8 => b"String is not valid UTF-8\0",
+            MnemonicError::InvalidEntropy => b"Entropy has to have 16, 20, 24, 28 or 32 bytes\0",
+            MnemonicError::InvalidWordCount => b"Mnemonic sentence could contain just 12, 15, 18, 21 or 24 words\0",
+            MnemonicError::InvalidWord => b"Mnemonic contains invalid word\0",
+            MnemonicError::InvalidChecksum => b"Invalid mnemonic checksum\0",
+            MnemonicError::BufferTooSmall => b"Output buffer is too small\0",
+        }
+    }
+}
+
+impl From<&mnemonic::MnemonicError> for MnemonicError {
+    fn from(error: &mnemonic::MnemonicError) -> MnemonicError {
+        match error {
+            mnemonic::MnemonicError::InvalidEntropy(_) => MnemonicError::InvalidEntropy,
+            mnemonic::MnemonicError::InvalidWordCount => MnemonicError::InvalidWordCount,
+            mnemonic::MnemonicError::InvalidWord(_) => MnemonicError::InvalidWord,
+            mnemonic::MnemonicError::InvalidChecksum => MnemonicError::InvalidChecksum,
+        }
+    }
+}
+
+/// Length of seed written by `mnemonic_to_seed`
+pub const MNEMONIC_SEED_LEN: usize = 64;
+
+/// Overwrite bytes with zeros, the writes are not optimized away
+fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: the pointer comes from a mutable reference
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
+
+/// Borrow NUL terminated UTF-8 string
+///
+/// # Safety
+///
+/// `string` has to be NULL or point to NUL terminated string valid for the returned lifetime.
+unsafe fn borrow_str<'a>(string: *const c_char) -> Result<&'a str, MnemonicError> {
+    if string.is_null() {
+        return Err(MnemonicError::NullPointer);
+    }
+    CStr::from_ptr(string).to_str().map_err(|_| MnemonicError::InvalidUtf8)
+}
+
+/// Copy bytes to buffer of the caller and store their length, the length is stored even if they do not fit
+///
+/// # Safety
+///
+/// `buffer` has to be valid for writes of `capacity` bytes, `len` has to be NULL or valid for writes.
+unsafe fn write_output(bytes: &[u8], buffer: *mut u8, capacity: usize, len: *mut usize) -> MnemonicError {
+    if !len.is_null() {
+        *len = bytes.len();
+    }
+    if bytes.len() > capacity {
+        return MnemonicError::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+    MnemonicError::Ok
+}
+
+/// Encode entropy as mnemonic
+///
+/// The mnemonic is written with terminating NUL, `mnemonic_len` receives its length without NUL. When the buffer is
+/// too small, `mnemonic_len` receives the required capacity including NUL instead.
+///
+/// # Safety
+///
+/// `entropy` has to be valid for reads of `entropy_len` bytes, `mnemonic` valid for writes of `mnemonic_capacity`
+/// bytes and `mnemonic_len` NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_from_entropy(entropy: *const u8, entropy_len: usize, mnemonic: *mut c_char, mnemonic_capacity: usize, mnemonic_len: *mut usize) -> i32 {
+    if entropy.is_null() || mnemonic.is_null() {
+        return MnemonicError::NullPointer as i32;
+    }
+    let entropy = std::slice::from_raw_parts(entropy, entropy_len);
+    if entropy.len() < 16 {
+        return MnemonicError::InvalidEntropy as i32;
+    }
+    let mut words = match mnemonic::entropy_to_mnemonic(entropy) {
+        Err(error) => return MnemonicError::from(&error) as i32,
+        Ok(words) => words.into_bytes(),
+    };
+    words.push(0);
+    let result = write_output(&words, mnemonic as *mut u8, mnemonic_capacity, mnemonic_len);
+    if result == MnemonicError::Ok && !mnemonic_len.is_null() {
+        *mnemonic_len -= 1;
+    }
+    zeroize(&mut words);
+    result as i32
+}
+
+/// Decode entropy of mnemonic, words may be separated by any whitespace and use any case
+///
+/// # Safety
+///
+/// `mnemonic` has to be NUL terminated string, `entropy` valid for writes of `entropy_capacity` bytes and
+/// `entropy_len` NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_to_entropy(mnemonic: *const c_char, entropy: *mut u8, entropy_capacity: usize, entropy_len: *mut usize) -> i32 {
+    if entropy.is_null() {
+        return MnemonicError::NullPointer as i32;
+    }
+    let mnemonic = match borrow_str(mnemonic) {
+        Err(error) => return error as i32,
+        Ok(mnemonic) => mnemonic,
+    };
+    match mnemonic::mnemonic_to_entropy(mnemonic) {
+        Err(error) => MnemonicError::from(&error) as i32,
+        Ok(mut decoded) => {
+            let result = write_output(&decoded, entropy, entropy_capacity, entropy_len);
+            zeroize(&mut decoded);
+            result as i32
+        },
+    }
+}
+
+/// Derive 64 bytes of BIP-39 seed from mnemonic
+///
+/// The checksum of the mnemonic is verified first, since seeds of mistyped mnemonics hold no funds.
+///
+/// # Safety
+///
+/// `mnemonic` has to be NUL terminated string, `passphrase` NULL or NUL terminated string and `seed` valid for
+/// writes of `seed_capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_to_seed(mnemonic: *const c_char, passphrase: *const c_char, seed: *mut u8, seed_capacity: usize) -> i32 {
+    if seed.is_null() {
+        return MnemonicError::NullPointer as i32;
+    }
+    let mnemonic = match borrow_str(mnemonic) {
+        Err(error) => return error as i32,
+        Ok(mnemonic) => mnemonic,
+    };
+    let passphrase = match passphrase.is_null() {
+        true => None,
+        false => match borrow_str(passphrase) {
+            Err(error) => return error as i32,
+            Ok(passphrase) => Some(passphrase),
+        },
+    };
+    if seed_capacity < MNEMONIC_SEED_LEN {
+        return MnemonicError::BufferTooSmall as i32;
+    }
+    match mnemonic::mnemonic_to_entropy(mnemonic) {
+        Err(error) => return MnemonicError::from(&error) as i32,
+        Ok(mut entropy) => zeroize(&mut entropy),
+    }
+    let mut derived = mnemonic::mnemonic_to_seed(mnemonic, passphrase);
+    let result = write_output(&derived, seed, seed_capacity, ptr::null_mut());
+    zeroize(&mut derived);
+    result as i32
+}
+
+/// Check that mnemonic has valid words and checksum
+///
+/// # Safety
+///
+/// `mnemonic` has to be NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_validate(mnemonic: *const c_char) -> i32 {
+    let mnemonic = match borrow_str(mnemonic) {
+        Err(error) => return error as i32,
+        Ok(mnemonic) => mnemonic,
+    };
+    match mnemonic::mnemonic_to_entropy(mnemonic) {
+        Err(error) => MnemonicError::from(&error) as i32,
+        Ok(mut entropy) => {
+            zeroize(&mut entropy);
+            MnemonicError::Ok as i32
+        },
+    }
+}
+
+/// Normalize whitespace and case of mnemonic, the result has to be released by `mnemonic_free`
+///
+/// Returns NULL when the mnemonic is NULL or not valid UTF-8, the words are not checked.
+///
+/// # Safety
+///
+/// `mnemonic` has to be NULL or NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_normalize(mnemonic: *const c_char) -> *mut c_char {
+    match borrow_str(mnemonic) {
+        Err(_) => ptr::null_mut(),
+        // NUL cannot be inside of C string
+        Ok(mnemonic) => CString::new(mnemonic::normalize_mnemonic(mnemonic)).expect("no NUL in C string").into_raw(),
+    }
+}
+
+/// Zeroize and release string returned by `mnemonic_normalize`
+///
+/// # Safety
+///
+/// `mnemonic` has to be NULL or returned by `mnemonic_normalize` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_free(mnemonic: *mut c_char) {
+    if !mnemonic.is_null() {
+        let mut bytes = CString::from_raw(mnemonic).into_bytes();
+        zeroize(&mut bytes);
+    }
+}
+
+/// Overwrite buffer of the caller with zeros, e.g. seed which is no longer needed
+///
+/// # Safety
+///
+/// `buffer` has to be NULL or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_zeroize(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        zeroize(std::slice::from_raw_parts_mut(buffer, len));
+    }
+}
+
+/// Static description of error code, never freed
+#[no_mangle]
+pub extern "C" fn mnemonic_error_message(code: i32) -> *const c_char {
+    let message = match MnemonicError::of_code(code) {
+        Some(error) => error.message(),
+        None => b"Unknown error\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn round_trip() {
+        let mut buffer = [0u8; 256];
+        let mut len = 0;
+        let code = unsafe { mnemonic_from_entropy([0u8; 16].as_ptr(), 16, buffer.as_mut_ptr() as *mut c_char, buffer.len(), &mut len) };
+        assert_eq!(code, MnemonicError::Ok as i32);
+        assert_eq!(&buffer[..len + 1], format!("{}\0", MNEMONIC).as_bytes());
+
+        let mnemonic = CString::new(MNEMONIC.to_uppercase()).unwrap();
+        let mut entropy = [0xffu8; 32];
+        assert_eq!(unsafe { mnemonic_to_entropy(mnemonic.as_ptr(), entropy.as_mut_ptr(), entropy.len(), &mut len) }, 0);
+        assert_eq!((len, &entropy[..16]), (16, &[0u8; 16][..]));
+        let mut seed = [0u8; MNEMONIC_SEED_LEN];
+        assert_eq!(unsafe { mnemonic_to_seed(mnemonic.as_ptr(), ptr::null(), seed.as_mut_ptr(), seed.len()) }, 0);
+        assert_eq!(seed.to_vec(), mnemonic::mnemonic_to_seed(MNEMONIC, None));
+        unsafe { mnemonic_zeroize(seed.as_mut_ptr(), seed.len()) };
+        assert_eq!(seed, [0u8; MNEMONIC_SEED_LEN]);
+
+        let normalized = unsafe { mnemonic_normalize(CString::new(format!(" {}\n", MNEMONIC.replace(' ', "\t"))).unwrap().as_ptr()) };
+        assert_eq!(unsafe { CStr::from_ptr(normalized) }.to_str(), Ok(MNEMONIC));
+        unsafe { mnemonic_free(normalized) };
+        assert!(unsafe { mnemonic_normalize(ptr::null()) }.is_null());
+    }
+
+    #[test]
+    fn error_codes() {
+        let mut small = [0u8; 8];
+        let mut len = 0;
+        let code = unsafe { mnemonic_from_entropy([0u8; 16].as_ptr(), 16, small.as_mut_ptr() as *mut c_char, small.len(), &mut len) };
+        assert_eq!((code, len), (MnemonicError::BufferTooSmall as i32, MNEMONIC.len() + 1));
+        assert_eq!(unsafe { mnemonic_from_entropy([0u8; 15].as_ptr(), 15, small.as_mut_ptr() as *mut c_char, small.len(), ptr::null_mut()) }, MnemonicError::InvalidEntropy as i32);
+        let validate = |mnemonic: &str| unsafe { mnemonic_validate(CString::new(mnemonic).unwrap().as_ptr()) };
+        assert_eq!(validate(MNEMONIC), MnemonicError::Ok as i32);
+        assert_eq!(validate("abandon about"), MnemonicError::InvalidWordCount as i32);
+        assert_eq!(validate(&MNEMONIC.replace("about", "xyz")), MnemonicError::InvalidWord as i32);
+        assert_eq!(validate(&MNEMONIC.replace("about", "zoo")), MnemonicError::InvalidChecksum as i32);
+        assert_eq!(unsafe { mnemonic_validate(ptr::null()) }, MnemonicError::NullPointer as i32);
+        assert_eq!(unsafe { mnemonic_validate(b"\xff\0".as_ptr() as *const c_char) }, MnemonicError::InvalidUtf8 as i32);
+        let message = unsafe { CStr::from_ptr(mnemonic_error_message(MnemonicError::InvalidChecksum as i32)) };
+        assert_eq!(message.to_str(), Ok("Invalid mnemonic checksum"));
+        let message = unsafe { CStr::from_ptr(mnemonic_error_message(MnemonicError::BufferTooSmall as i32 + 1)) };
+        assert_eq!(message.to_str(), Ok("Unknown error"));
+    }
+}
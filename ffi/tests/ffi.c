@@ -0,0 +1,55 @@
+/* Exercises the C interface through include/mnemonic.h, exits with number of failed checks */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "mnemonic.h"
+
+static const char *MNEMONIC = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+static const uint8_t SEED_PREFIX[] = {0x5e, 0xb0, 0x0b, 0xbd, 0xdc, 0xf0, 0x69, 0x08};
+
+static int failures = 0;
+
+static void check(int condition, const char *description) {
+    if (!condition) {
+        fprintf(stderr, "FAILED: %s\n", description);
+        failures++;
+    }
+}
+
+int main(void) {
+    uint8_t entropy[32] = {0};
+    char mnemonic[256];
+    size_t len = 0;
+
+    check(mnemonic_from_entropy(entropy, 16, mnemonic, sizeof mnemonic, &len) == MNEMONIC_ERROR_OK, "encode entropy");
+    check(len == strlen(MNEMONIC) && strcmp(mnemonic, MNEMONIC) == 0, "encoded mnemonic");
+    check(mnemonic_from_entropy(entropy, 16, mnemonic, 8, &len) == MNEMONIC_ERROR_BUFFER_TOO_SMALL, "small buffer");
+    check(len == strlen(MNEMONIC) + 1, "required capacity");
+    check(mnemonic_from_entropy(entropy, 16, mnemonic, len, &len) == MNEMONIC_ERROR_OK, "exact buffer");
+    check(len == strlen(MNEMONIC) && strcmp(mnemonic, MNEMONIC) == 0, "mnemonic in exact buffer");
+    check(mnemonic_from_entropy(entropy, 15, mnemonic, sizeof mnemonic, NULL) == MNEMONIC_ERROR_INVALID_ENTROPY, "invalid entropy");
+
+    memset(entropy, 0xff, sizeof entropy);
+    check(mnemonic_to_entropy(MNEMONIC, entropy, sizeof entropy, &len) == MNEMONIC_ERROR_OK, "decode mnemonic");
+    check(len == 16 && entropy[0] == 0 && entropy[15] == 0, "decoded entropy");
+
+    uint8_t seed[MNEMONIC_SEED_LEN];
+    check(mnemonic_to_seed(MNEMONIC, NULL, seed, sizeof seed) == MNEMONIC_ERROR_OK, "derive seed");
+    check(memcmp(seed, SEED_PREFIX, sizeof SEED_PREFIX) == 0, "derived seed");
+    check(mnemonic_to_seed(MNEMONIC, "", seed, 32) == MNEMONIC_ERROR_BUFFER_TOO_SMALL, "small seed buffer");
+    mnemonic_zeroize(seed, sizeof seed);
+    check(seed[0] == 0 && seed[MNEMONIC_SEED_LEN - 1] == 0, "zeroized seed");
+
+    check(mnemonic_validate(MNEMONIC) == MNEMONIC_ERROR_OK, "valid mnemonic");
+    check(mnemonic_validate("abandon about") == MNEMONIC_ERROR_INVALID_WORD_COUNT, "word count");
+    check(mnemonic_validate(NULL) == MNEMONIC_ERROR_NULL_POINTER, "NULL mnemonic");
+    check(mnemonic_validate("\xff") == MNEMONIC_ERROR_INVALID_UTF8, "invalid UTF-8");
+    check(strcmp(mnemonic_error_message(MNEMONIC_ERROR_INVALID_CHECKSUM), "Invalid mnemonic checksum") == 0, "error message");
+
+    char *normalized = mnemonic_normalize("  ABANDON abandon\tabandon abandon abandon abandon abandon abandon abandon abandon abandon about\n");
+    check(normalized != NULL && strcmp(normalized, MNEMONIC) == 0, "normalized mnemonic");
+    mnemonic_free(normalized);
+
+    return failures;
+}
@@ -0,0 +1,53 @@
+//! Compiles `tests/ffi.c` against `include/mnemonic.h` and the static library and runs it, checks that the header is
+//! generated from `src/lib.rs`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory of the static library, which cargo builds with the profile of this test before running it
+fn library_dir() -> PathBuf {
+    // target/<profile>/deps/ffi-<hash> next to libmnemonic_ffi.a
+    std::env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_program() {
+    let library_dir = library_dir();
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let temp_dir = tempfile::tempdir().unwrap();
+    let program = temp_dir.path().join("ffi");
+    let compiled = Command::new(std::env::var("CC").unwrap_or_else(|_| String::from("cc")))
+        .arg(manifest_dir.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(library_dir.join("libmnemonic_ffi.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(compiled.success(), "C compiler failed");
+    let output = Command::new(&program).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn generated_header() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let temp_dir = tempfile::tempdir().unwrap();
+    let header = temp_dir.path().join("mnemonic.h");
+    let generated = Command::new("cbindgen")
+        .current_dir(manifest_dir)
+        .args(["--config", "cbindgen.toml", "--output"])
+        .arg(&header)
+        .arg("src/lib.rs")
+        .status();
+    match generated {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => eprintln!("cbindgen is not installed, header is not checked"),
+        Err(error) => panic!("cbindgen cannot be run: {}", error),
+        Ok(status) => {
+            assert!(status.success(), "cbindgen failed");
+            let expected = std::fs::read_to_string(manifest_dir.join("include/mnemonic.h")).unwrap();
+            assert_eq!(std::fs::read_to_string(&header).unwrap(), expected, "include/mnemonic.h is not generated from src/lib.rs");
+        },
+    }
+}
@@ -0,0 +1,303 @@
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::PrimeField;
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+use ripemd::{Digest as RipemdDigest, Ripemd160};
+use crate::bip32::{format_path, parse_path, ExtendedPrivateKey, HARDENED};
+use crate::codex32::{bytes_to_values, CHARSET};
+use crate::sha256;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+/// Bitcoin network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+/// Type of single-key output script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressType {
+    /// Legacy pay to public key hash (BIP-44)
+    P2pkh,
+    /// Segwit nested in pay to script hash (BIP-49)
+    P2shP2wpkh,
+    /// Native segwit v0 (BIP-84)
+    P2wpkh,
+    /// Taproot key path (BIP-86)
+    P2tr,
+}
+
+impl std::str::FromStr for Network {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Network, String> {
+        match name {
+            "mainnet" | "bitcoin" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(format!("Unknown network: {}", name)),
+        }
+    }
+}
+
+impl Network {
+    /// SLIP-44 coin type used in derivation paths
+    pub fn coin_type(self) -> u32 {
+        match self {
+            Network::Mainnet => 0,
+            Network::Testnet | Network::Regtest => 1,
+        }
+    }
+
+    /// Human readable part of segwit addresses
+    fn hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+
+    /// Base58 version bytes of P2PKH and P2SH addresses
+    fn base58_versions(self) -> (u8, u8) {
+        match self {
+            Network::Mainnet => (0x00, 0x05),
+            Network::Testnet | Network::Regtest => (0x6f, 0xc4),
+        }
+    }
+}
+
+impl std::str::FromStr for AddressType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<AddressType, String> {
+        match name {
+            "p2pkh" | "legacy" => Ok(AddressType::P2pkh),
+            "p2sh-p2wpkh" | "nested" => Ok(AddressType::P2shP2wpkh),
+            "p2wpkh" | "segwit" => Ok(AddressType::P2wpkh),
+            "p2tr" | "taproot" => Ok(AddressType::P2tr),
+            _ => Err(format!("Unknown address type: {}", name)),
+        }
+    }
+}
+
+impl AddressType {
+    /// BIP-43 purpose of derivation paths for the address type
+    pub fn purpose(self) -> u32 {
+        match self {
+            AddressType::P2pkh => 44,
+            AddressType::P2shP2wpkh => 49,
+            AddressType::P2wpkh => 84,
+            AddressType::P2tr => 86,
+        }
+    }
+
+    /// Address type belonging to BIP-43 purpose
+    pub fn from_purpose(purpose: u32) -> Option<AddressType> {
+        match purpose {
+            44 => Some(AddressType::P2pkh),
+            49 => Some(AddressType::P2shP2wpkh),
+            84 => Some(AddressType::P2wpkh),
+            86 => Some(AddressType::P2tr),
+            _ => None,
+        }
+    }
+
+    /// Path of external chain of the first account, e.g. m/84'/0'/0'/0
+    pub fn default_path(self, network: Network) -> String {
+        format_path(&[self.purpose() + HARDENED, network.coin_type() + HARDENED, HARDENED, 0])
+    }
+}
+
+/// Compute ripemd160(sha256(input))
+pub fn hash160(input: &[u8]) -> Vec<u8> {
+    let mut hasher = Ripemd160::new();
+    hasher.update(sha256(input));
+    hasher.finalize().to_vec()
+}
+
+/// Compute BIP-340 tagged hash
+fn tagged_hash(tag: &str, input: &[u8]) -> Vec<u8> {
+    let tag_hash = sha256(tag.as_bytes());
+    let mut data = tag_hash.clone();
+    data.extend_from_slice(&tag_hash);
+    data.extend_from_slice(input);
+    sha256(&data)
+}
+
+/// Encode data with version prefix and checksum in base58
+fn base58check(version: u8, payload: &[u8]) -> String {
+    let mut data = vec![version];
+    data.extend_from_slice(payload);
+    let checksum = sha256(&sha256(&data));
+    data.extend_from_slice(&checksum[..4]);
+
+    // repeated division of big endian number by 58
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &data {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    let mut result = "1".repeat(zeros);
+    result.extend(digits.iter().rev().map(|&digit| BASE58_ALPHABET[digit as usize] as char));
+    result
+}
+
+/// Compute bech32 checksum polynomial
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    let mut checksum = 1u32;
+    for &value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ u32::from(value);
+        for (idx, gen) in GENERATOR.iter().enumerate() {
+            if (top >> idx) & 1 != 0 {
+                checksum ^= gen;
+            }
+        }
+    }
+    checksum
+}
+
+/// Encode witness program as segwit address, bech32 for version 0 and bech32m otherwise
+fn segwit_address(hrp: &str, version: u8, program: &[u8]) -> String {
+    let mut data = vec![version];
+    data.extend(bytes_to_values(program));
+
+    let mut values: Vec<u8> = hrp.bytes().map(|byte| byte >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|byte| byte & 31));
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0u8; 6]);
+    let constant = if version == 0 { BECH32_CONST } else { BECH32M_CONST };
+    let residue = bech32_polymod(&values) ^ constant;
+    data.extend((0..6).map(|i| ((residue >> (5 * (5 - i))) & 31) as u8));
+
+    let mut result = format!("{}1", hrp);
+    result.extend(data.iter().map(|&value| CHARSET.as_bytes()[value as usize] as char));
+    result
+}
+
+/// Compute BIP-86 output key, i.e. internal key tweaked with its own tagged hash
+fn taproot_output_key(public_key: &[u8; 33]) -> Result<[u8; 32], String> {
+    let encoded = EncodedPoint::from_bytes(&public_key[..]).map_err(|_| String::from("Invalid public key!"))?;
+    let point: Option<AffinePoint> = Option::from(AffinePoint::from_encoded_point(&encoded));
+    let mut point = ProjectivePoint::from(point.ok_or_else(|| String::from("Invalid public key!"))?);
+    if public_key[0] == 0x03 {
+        // x-only keys imply even y coordinate
+        point = -point;
+    }
+    let tweak = tagged_hash("TapTweak", &public_key[1..]);
+    let tweak: Option<Scalar> = Option::from(Scalar::from_repr(FieldBytes::clone_from_slice(&tweak)));
+    let output = (point + ProjectivePoint::GENERATOR * tweak.ok_or_else(|| String::from("Invalid taproot tweak!"))?).to_affine();
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&output.to_encoded_point(true).as_bytes()[1..]);
+    Ok(result)
+}
+
+/// Compute address of compressed public key
+pub fn address(public_key: &[u8; 33], address_type: AddressType, network: Network) -> Result<String, String> {
+    let (p2pkh_version, p2sh_version) = network.base58_versions();
+    Ok(match address_type {
+        AddressType::P2pkh => base58check(p2pkh_version, &hash160(public_key)),
+        AddressType::P2shP2wpkh => {
+            let mut redeem_script = vec![0x00, 0x14];
+            redeem_script.extend(hash160(public_key));
+            base58check(p2sh_version, &hash160(&redeem_script))
+        },
+        AddressType::P2wpkh => segwit_address(network.hrp(), 0, &hash160(public_key)),
+        AddressType::P2tr => segwit_address(network.hrp(), 1, &taproot_output_key(public_key)?),
+    })
+}
+
+/// Derive the first `count` addresses below path from seed
+///
+/// # Arguments
+///
+/// * `seed` - seed from `mnemonic_to_seed`
+/// * `path` - derivation path of the chain, addresses are derived at its children 0..count
+/// * `address_type` - type of output script
+/// * `network` - network of addresses
+/// * `count` - number of addresses, their indices have to be non-hardened
+///
+/// Returns pairs of full derivation path and address.
+pub fn derive_addresses(seed: &[u8], path: &str, address_type: AddressType, network: Network, count: u32) -> Result<Vec<(String, String)>, String> {
+    if count > HARDENED {
+        return Err(String::from("Address indices have to be below 2^31!"));
+    }
+    let indices = parse_path(path)?;
+    let chain = ExtendedPrivateKey::new_master(seed)?.derive_path(&indices)?;
+    let mut result = Vec::new();
+    for index in 0..count {
+        let key = chain.derive_child(index)?;
+        let mut full_path = indices.clone();
+        full_path.push(index);
+        result.push((format_path(&full_path), address(&key.public_key(), address_type, network)?));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mnemonic_to_seed;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn first_address(address_type: AddressType, network: Network) -> String {
+        let seed = mnemonic_to_seed(MNEMONIC, None);
+        let addresses = derive_addresses(&seed, &address_type.default_path(network), address_type, network, 1).unwrap();
+        addresses[0].1.clone()
+    }
+
+    #[test]
+    fn bip44_p2pkh() {
+        assert_eq!(first_address(AddressType::P2pkh, Network::Mainnet), "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA");
+    }
+
+    #[test]
+    fn bip49_p2sh_p2wpkh() {
+        assert_eq!(first_address(AddressType::P2shP2wpkh, Network::Mainnet), "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf");
+    }
+
+    #[test]
+    fn bip84_p2wpkh() {
+        assert_eq!(first_address(AddressType::P2wpkh, Network::Mainnet), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+    }
+
+    #[test]
+    fn bip86_p2tr() {
+        assert_eq!(first_address(AddressType::P2tr, Network::Mainnet), "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr");
+    }
+
+    #[test]
+    fn networks() {
+        assert!(first_address(AddressType::P2wpkh, Network::Testnet).starts_with("tb1q"));
+        assert!(first_address(AddressType::P2wpkh, Network::Regtest).starts_with("bcrt1q"));
+        assert!(first_address(AddressType::P2pkh, Network::Testnet).starts_with(['m', 'n']));
+        assert!(first_address(AddressType::P2shP2wpkh, Network::Regtest).starts_with('2'));
+        assert_eq!(AddressType::P2tr.default_path(Network::Testnet), "m/86'/1'/0'/0");
+    }
+
+    #[test]
+    fn address_paths() {
+        let seed = mnemonic_to_seed(MNEMONIC, None);
+        let addresses = derive_addresses(&seed, "m/84'/0'/0'/0", AddressType::P2wpkh, Network::Mainnet, 3).unwrap();
+        assert_eq!(addresses.len(), 3);
+        assert_eq!(addresses[2].0, "m/84'/0'/0'/0/2");
+        assert!(derive_addresses(&seed, "m/84'/0'/0'/0", AddressType::P2wpkh, Network::Mainnet, HARDENED + 1).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use aes::Block;
+use aes::hazmat::cipher_round;
+use blake2::Blake2bVar;
+use blake2::digest::{Update, VariableOutput};
+
+const BLOCK_SIZE: usize = 16;
+const EXTRACTED_KEY_SIZE: usize = 3 * BLOCK_SIZE;
+
+type Block16 = [u8; BLOCK_SIZE];
+
+/// AEZ v5 keyed state
+///
+/// Only AEZ-tiny is implemented, therefore the enciphered message (plaintext followed by `tau`
+/// zero bytes) has to be shorter than 32 bytes. This is sufficient for aezeed.
+pub struct Aez {
+    i: Block16,
+    j: Block16,
+    l: Block16,
+}
+
+/// XOR two blocks
+fn xor_block(lhs: &Block16, rhs: &Block16) -> Block16 {
+    let mut result = [0u8; BLOCK_SIZE];
+    for idx in 0..BLOCK_SIZE {
+        result[idx] = lhs[idx] ^ rhs[idx];
+    }
+    result
+}
+
+/// Multiply block by x in GF(2^128)
+fn double_block(block: &Block16) -> Block16 {
+    let mut result = [0u8; BLOCK_SIZE];
+    for idx in 0..BLOCK_SIZE - 1 {
+        result[idx] = (block[idx] << 1) | (block[idx + 1] >> 7);
+    }
+    result[BLOCK_SIZE - 1] = block[BLOCK_SIZE - 1] << 1;
+    if block[0] & 0x80 != 0 {
+        result[BLOCK_SIZE - 1] ^= 0x87;
+    }
+    result
+}
+
+/// Multiply block by small integer in GF(2^128)
+fn mult_block(factor: usize, block: &Block16) -> Block16 {
+    let mut result = [0u8; BLOCK_SIZE];
+    let mut power = *block;
+    let mut factor = factor;
+    while factor > 0 {
+        if factor & 1 != 0 {
+            result = xor_block(&result, &power);
+        }
+        power = double_block(&power);
+        factor >>= 1;
+    }
+    result
+}
+
+impl Aez {
+    /// Create AEZ state from key of arbitrary length
+    pub fn new(key: &[u8]) -> Aez {
+        let mut extracted = [0u8; EXTRACTED_KEY_SIZE];
+        if key.len() == EXTRACTED_KEY_SIZE {
+            extracted.copy_from_slice(key);
+        } else {
+            let mut hasher = Blake2bVar::new(EXTRACTED_KEY_SIZE).unwrap();
+            hasher.update(key);
+            hasher.finalize_variable(&mut extracted).unwrap();
+        }
+
+        let mut aez = Aez { i: [0u8; BLOCK_SIZE], j: [0u8; BLOCK_SIZE], l: [0u8; BLOCK_SIZE] };
+        aez.i.copy_from_slice(&extracted[0..16]);
+        aez.j.copy_from_slice(&extracted[16..32]);
+        aez.l.copy_from_slice(&extracted[32..48]);
+        aez
+    }
+
+    /// AES4 with round keys (J, I, L, 0)
+    fn aes4(&self, input: &Block16) -> Block16 {
+        let mut state = Block::from(*input);
+        for key in [&self.j, &self.i, &self.l, &[0u8; BLOCK_SIZE]].iter() {
+            cipher_round(&mut state, &Block::from(**key));
+        }
+        state.into()
+    }
+
+    /// Tweakable block cipher E^{j,i} for j >= 0
+    fn e(&self, j: usize, i: usize, input: &Block16) -> Block16 {
+        let mut i_power = self.i;
+        for _ in 0..i.div_ceil(8) {
+            i_power = double_block(&i_power);
+        }
+        let tweak = xor_block(&xor_block(&mult_block(j, &self.j), &i_power), &mult_block(i % 8, &self.l));
+        self.aes4(&xor_block(input, &tweak))
+    }
+
+    /// AEZ-hash of the vector (tau, nonce, ad_1, ..., ad_n)
+    fn hash(&self, nonce: &[u8], ad: &[&[u8]], tau: usize) -> Block16 {
+        let mut tau_block = [0u8; BLOCK_SIZE];
+        tau_block[12..].copy_from_slice(&((tau * 8) as u32).to_be_bytes());
+        let mut result = self.e(3, 1, &tau_block);
+
+        let strings = std::iter::once(nonce).chain(ad.iter().cloned());
+        for (idx, string) in strings.enumerate() {
+            let j = idx + 4;
+            if string.is_empty() {
+                let mut padded = [0u8; BLOCK_SIZE];
+                padded[0] = 0x80;
+                result = xor_block(&result, &self.e(j, 0, &padded));
+            }
+            for (i, chunk) in string.chunks(BLOCK_SIZE).enumerate() {
+                let mut block = [0u8; BLOCK_SIZE];
+                block[..chunk.len()].copy_from_slice(chunk);
+                if chunk.len() == BLOCK_SIZE {
+                    result = xor_block(&result, &self.e(j, i + 1, &block));
+                } else {
+                    block[chunk.len()] = 0x80;
+                    result = xor_block(&result, &self.e(j, 0, &block));
+                }
+            }
+        }
+        result
+    }
+
+    /// AEZ-tiny Feistel network for inputs shorter than 32 bytes
+    fn tiny(&self, delta: &Block16, input: &[u8], decrypt: bool) -> Vec<u8> {
+        let len = input.len();
+        let half = len.div_ceil(2);
+        let (rounds, tweak_idx) = match len {
+            1 => (24, 7),
+            2 => (16, 7),
+            _ if len < BLOCK_SIZE => (10, 7),
+            _ => (8, 6),
+        };
+
+        let mut left = [0u8; BLOCK_SIZE];
+        let mut right = [0u8; BLOCK_SIZE];
+        left[..half].copy_from_slice(&input[..half]);
+        right[..half].copy_from_slice(&input[len / 2..len / 2 + half]);
+
+        // input of odd length is split in the middle of a byte
+        let (mask, pad) = if len % 2 == 1 {
+            for idx in 0..len / 2 {
+                right[idx] = (right[idx] << 4) | (right[idx + 1] >> 4);
+            }
+            right[len / 2] <<= 4;
+            (0xf0u8, 0x08u8)
+        } else {
+            (0x00u8, 0x80u8)
+        };
+
+        if decrypt && len < BLOCK_SIZE {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..len].copy_from_slice(input);
+            block[0] |= 0x80;
+            left[0] ^= self.e(0, 3, &xor_block(&block, delta))[0] & 0x80;
+        }
+
+        let round_block = |half_block: &Block16, round: usize| {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..half].copy_from_slice(&half_block[..half]);
+            block[len / 2] = (block[len / 2] & mask) | pad;
+            block = xor_block(&block, delta);
+            block[BLOCK_SIZE - 1] ^= round as u8;
+            self.e(0, tweak_idx, &block)
+        };
+
+        for step in 0..rounds / 2 {
+            let (first, second) = if decrypt {
+                (rounds - 1 - 2 * step, rounds - 2 - 2 * step)
+            } else {
+                (2 * step, 2 * step + 1)
+            };
+            left = xor_block(&left, &round_block(&right, first));
+            right = xor_block(&right, &round_block(&left, second));
+        }
+
+        let mut output = vec![0u8; len];
+        output[..len / 2].copy_from_slice(&right[..len / 2]);
+        output[len / 2..].copy_from_slice(&left[..half]);
+        if len % 2 == 1 {
+            for idx in (len / 2 + 1..len).rev() {
+                output[idx] = (output[idx] >> 4) | (output[idx - 1] << 4);
+            }
+            output[len / 2] = (left[0] >> 4) | (right[len / 2] & 0xf0);
+        }
+
+        if !decrypt && len < BLOCK_SIZE {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..len].copy_from_slice(&output);
+            block[0] |= 0x80;
+            output[0] ^= self.e(0, 3, &xor_block(&block, delta))[0] & 0x80;
+        }
+        output
+    }
+
+    /// Encrypt plaintext, expanding it by `tau` bytes of authenticator
+    pub fn encrypt(&self, nonce: &[u8], ad: &[&[u8]], tau: usize, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        if plaintext.is_empty() || plaintext.len() + tau >= 2 * BLOCK_SIZE {
+            return Err(String::from("Unsupported AEZ message length!"));
+        }
+        let delta = self.hash(nonce, ad, tau);
+        let mut input = plaintext.to_vec();
+        input.resize(plaintext.len() + tau, 0);
+        Ok(self.tiny(&delta, &input, false))
+    }
+
+    /// Decrypt ciphertext and verify its `tau` bytes of authenticator
+    pub fn decrypt(&self, nonce: &[u8], ad: &[&[u8]], tau: usize, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        if ciphertext.len() <= tau || ciphertext.len() >= 2 * BLOCK_SIZE {
+            return Err(String::from("Unsupported AEZ message length!"));
+        }
+        let delta = self.hash(nonce, ad, tau);
+        let mut output = self.tiny(&delta, ciphertext, true);
+        let plaintext_len = ciphertext.len() - tau;
+        if output[plaintext_len..].iter().any(|&byte| byte != 0) {
+            return Err(String::from("Invalid AEZ ciphertext!"));
+        }
+        output.truncate(plaintext_len);
+        Ok(output)
+    }
+}
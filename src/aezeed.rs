@@ -0,0 +1,218 @@
+use crate::{get_word, mnemonic_lookup};
+use crate::aez::Aez;
+
+/// Version of the enciphered seed format
+pub const CIPHER_SEED_VERSION: u8 = 0;
+/// Passphrase used by LND when none is given
+pub const DEFAULT_PASSPHRASE: &str = "aezeed";
+/// Unix timestamp of the Bitcoin genesis block, birthday is counted in days since then
+pub const BITCOIN_GENESIS_TIMESTAMP: u64 = 1_231_006_505;
+
+pub const ENTROPY_SIZE: usize = 16;
+pub const SALT_SIZE: usize = 5;
+pub const NUM_MNEMONIC_WORDS: usize = 24;
+
+const DECIPHERED_SIZE: usize = 19;
+const ENCIPHERED_SIZE: usize = 33;
+const CIPHERTEXT_EXPANSION: usize = 4;
+const KEY_SIZE: usize = 32;
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Deciphered contents of an aezeed mnemonic
+#[derive(Debug, Clone, PartialEq)]
+pub struct CipherSeed {
+    pub internal_version: u8,
+    pub birthday: u16,
+    pub entropy: [u8; ENTROPY_SIZE],
+    pub salt: [u8; SALT_SIZE],
+}
+
+/// Compute CRC-32C (Castagnoli) of input
+fn crc32c(input: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in input {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82f6_3b78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Derive encryption key from passphrase and salt using scrypt with N = 2^`log_n`
+fn derive_key(passphrase: Option<&str>, salt: &[u8], log_n: u8) -> [u8; KEY_SIZE] {
+    let passphrase = match passphrase {
+        None | Some("") => DEFAULT_PASSPHRASE,
+        Some(passphrase) => passphrase,
+    };
+    let params = scrypt::Params::new(log_n, SCRYPT_R, SCRYPT_P, KEY_SIZE).unwrap();
+    let mut key = [0u8; KEY_SIZE];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key).unwrap();
+    key
+}
+
+/// Build associated data (version followed by salt)
+fn associated_data(salt: &[u8]) -> Vec<u8> {
+    let mut ad = vec![CIPHER_SEED_VERSION];
+    ad.extend_from_slice(salt);
+    ad
+}
+
+/// Convert days since the genesis block from unix timestamp
+pub fn birthday_from_timestamp(timestamp: u64) -> u16 {
+    (timestamp.saturating_sub(BITCOIN_GENESIS_TIMESTAMP) / (24 * 60 * 60)) as u16
+}
+
+impl CipherSeed {
+    /// Create cipher seed from its parts
+    ///
+    /// # Arguments
+    ///
+    /// * `internal_version` - version of the key derivation scheme
+    /// * `birthday` - days since the genesis block, see `birthday_from_timestamp`
+    /// * `entropy` - wallet entropy
+    /// * `salt` - random salt of scrypt
+    pub fn new(internal_version: u8, birthday: u16, entropy: [u8; ENTROPY_SIZE], salt: [u8; SALT_SIZE]) -> CipherSeed {
+        CipherSeed { internal_version, birthday, entropy, salt }
+    }
+
+    /// Encipher the seed and encode it as 24 words
+    ///
+    /// Empty or missing passphrase is replaced by `DEFAULT_PASSPHRASE`.
+    pub fn to_mnemonic(&self, passphrase: Option<&str>) -> Result<String, String> {
+        self.encipher(passphrase, SCRYPT_LOG_N)
+    }
+
+    /// Decode 24 words, verify checksum and decipher the seed
+    ///
+    /// Empty or missing passphrase is replaced by `DEFAULT_PASSPHRASE`.
+    pub fn from_mnemonic(mnemonic: &str, passphrase: Option<&str>) -> Result<CipherSeed, String> {
+        CipherSeed::decipher(mnemonic, passphrase, SCRYPT_LOG_N)
+    }
+
+    fn encipher(&self, passphrase: Option<&str>, scrypt_log_n: u8) -> Result<String, String> {
+        let mut plaintext = Vec::with_capacity(DECIPHERED_SIZE);
+        plaintext.push(self.internal_version);
+        plaintext.extend_from_slice(&self.birthday.to_be_bytes());
+        plaintext.extend_from_slice(&self.entropy);
+
+        let key = derive_key(passphrase, &self.salt, scrypt_log_n);
+        let ad = associated_data(&self.salt);
+        let ciphertext = Aez::new(&key).encrypt(&[], &[&ad], CIPHERTEXT_EXPANSION, &plaintext)?;
+
+        let mut enciphered = Vec::with_capacity(ENCIPHERED_SIZE);
+        enciphered.push(CIPHER_SEED_VERSION);
+        enciphered.extend_from_slice(&ciphertext);
+        enciphered.extend_from_slice(&self.salt);
+        let checksum = crc32c(&enciphered);
+        enciphered.extend_from_slice(&checksum.to_be_bytes());
+
+        let words: Vec<_> = (0..NUM_MNEMONIC_WORDS).map(|position| get_word(position, &enciphered)).collect();
+        Ok(words.join(" "))
+    }
+
+    fn decipher(mnemonic: &str, passphrase: Option<&str>, scrypt_log_n: u8) -> Result<CipherSeed, String> {
+        let words: Vec<_> = mnemonic.split_whitespace().collect();
+        if words.len() != NUM_MNEMONIC_WORDS {
+            return Err(String::from("Aezeed mnemonic has to contain exactly 24 words!"));
+        }
+
+        let mut enciphered = [0u8; ENCIPHERED_SIZE];
+        let mut pos = 0usize;
+        for word in words {
+            let index = mnemonic_lookup(word)?;
+            for offset in 0..11 {
+                if index & (1024 >> offset) != 0 {
+                    enciphered[pos / 8] |= 128u8 >> (pos % 8);
+                }
+                pos += 1;
+            }
+        }
+
+        if enciphered[0] != CIPHER_SEED_VERSION {
+            return Err(format!("Unsupported aezeed version: {}", enciphered[0]));
+        }
+        let mut checksum = [0u8; 4];
+        checksum.copy_from_slice(&enciphered[ENCIPHERED_SIZE - 4..]);
+        if crc32c(&enciphered[..ENCIPHERED_SIZE - 4]) != u32::from_be_bytes(checksum) {
+            return Err(String::from("Invalid aezeed checksum!"));
+        }
+
+        let ciphertext = &enciphered[1..1 + DECIPHERED_SIZE + CIPHERTEXT_EXPANSION];
+        let mut salt = [0u8; SALT_SIZE];
+        salt.copy_from_slice(&enciphered[ENCIPHERED_SIZE - 4 - SALT_SIZE..ENCIPHERED_SIZE - 4]);
+
+        let key = derive_key(passphrase, &salt, scrypt_log_n);
+        let ad = associated_data(&salt);
+        let plaintext = Aez::new(&key).decrypt(&[], &[&ad], CIPHERTEXT_EXPANSION, ciphertext)
+            .map_err(|_| String::from("Invalid aezeed passphrase!"))?;
+
+        let mut entropy = [0u8; ENTROPY_SIZE];
+        entropy.copy_from_slice(&plaintext[3..]);
+        Ok(CipherSeed {
+            internal_version: plaintext[0],
+            birthday: u16::from_be_bytes([plaintext[1], plaintext[2]]),
+            entropy,
+            salt,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ENTROPY: [u8; ENTROPY_SIZE] = [0x81, 0xb6, 0x37, 0xd8, 0x63, 0x59, 0xe6, 0x96, 0x0d, 0xe7, 0x95, 0xe4, 0x1e, 0x0b, 0x4c, 0xfd];
+    const TEST_SALT: [u8; SALT_SIZE] = *b"salt1";
+    // LND generates its test vectors with scrypt N = 16
+    const TEST_SCRYPT_LOG_N: u8 = 4;
+
+    #[test]
+    fn crc32c_check() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn birthday() {
+        assert_eq!(birthday_from_timestamp(BITCOIN_GENESIS_TIMESTAMP), 0);
+        assert_eq!(birthday_from_timestamp(1_521_799_345), 3365);
+    }
+
+    #[test]
+    fn lnd_tv1() {
+        let mnemonic = "ability liquid travel stem barely drastic pact cupboard apple thrive morning oak feature tissue couch old math inform success suggest drink motion know royal";
+        let seed = CipherSeed::new(0, 0, TEST_ENTROPY, TEST_SALT);
+        assert_eq!(seed.encipher(None, TEST_SCRYPT_LOG_N).unwrap(), mnemonic);
+        assert_eq!(CipherSeed::decipher(mnemonic, None, TEST_SCRYPT_LOG_N).unwrap(), seed);
+    }
+
+    #[test]
+    fn lnd_tv2() {
+        let mnemonic = "able tree stool crush transfer cloud cross three profit outside hen citizen plate ride require leg siren drum success suggest drink require fiscal upgrade";
+        let passphrase = "!very_safe_55345_password*";
+        let seed = CipherSeed::new(0, birthday_from_timestamp(1_521_799_345), TEST_ENTROPY, TEST_SALT);
+        assert_eq!(seed.encipher(Some(passphrase), TEST_SCRYPT_LOG_N).unwrap(), mnemonic);
+        assert_eq!(CipherSeed::decipher(mnemonic, Some(passphrase), TEST_SCRYPT_LOG_N).unwrap(), seed);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let seed = CipherSeed::new(0, 4242, TEST_ENTROPY, TEST_SALT);
+        let mnemonic = seed.to_mnemonic(Some("secret")).unwrap();
+        assert_eq!(CipherSeed::from_mnemonic(&mnemonic, Some("secret")).unwrap(), seed);
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let mnemonic = CipherSeed::new(0, 42, TEST_ENTROPY, TEST_SALT).encipher(Some("secret"), TEST_SCRYPT_LOG_N).unwrap();
+        assert!(CipherSeed::decipher(&mnemonic, Some("wrong"), TEST_SCRYPT_LOG_N).is_err());
+    }
+
+    #[test]
+    fn wrong_checksum() {
+        let mnemonic = "ability liquid travel stem barely drastic pact cupboard apple thrive morning oak feature tissue couch old math inform success suggest drink motion know zoo";
+        assert!(CipherSeed::decipher(mnemonic, None, TEST_SCRYPT_LOG_N).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Printable backup sheets of mnemonics for paper or metal backups
+//!
+//! The sheet lists numbered words in a grid with their 4-letter prefixes and word indices in decimal and 11-bit binary
+//! for punch plates. Checksum bits of the last word are highlighted and the master key fingerprint allows to verify
+//! the restored wallet later. Sheets are A4 pages rendered as SVG or PDF without any external resources.
+
+use crate::{fingerprint, mnemonic_lookup, mnemonic_to_entropy, normalize_mnemonic};
+
+/// A4 page size in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 40.0;
+const FONT_SIZE: f32 = 10.0;
+const ROW_HEIGHT: f32 = 24.0;
+/// Width of one character of monospace font in ems
+const CHAR_WIDTH: f32 = 0.6;
+/// Color of checksum bits, RGB in 0 to 1
+const HIGHLIGHT: [f32; 3] = [0.8, 0.0, 0.0];
+
+/// One word of the backup sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupWord {
+    /// Position of the word, starting at 1
+    pub number: usize,
+    pub word: String,
+    /// First four letters, which identify the word
+    pub prefix: String,
+    /// Index in word list
+    pub index: u16,
+    /// Index as 11-bit binary string
+    pub binary: String,
+}
+
+/// Content of backup sheet of mnemonic
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupSheet {
+    pub words: Vec<BackupWord>,
+    /// Number of checksum bits at the end of the last word
+    pub checksum_bits: usize,
+    /// Fingerprint of the master key in hexadecimal
+    pub fingerprint: String,
+}
+
+/// Text or cell border on page, coordinates from the top left corner in points
+enum Element {
+    Text { x: f32, y: f32, size: f32, bold: bool, highlight: bool, text: String },
+    Rect { x: f32, y: f32, width: f32, height: f32 },
+}
+
+impl BackupSheet {
+    /// Create backup sheet of mnemonic
+    ///
+    /// # Arguments
+    ///
+    /// * `mnemonic` - mnemonic with valid checksum
+    /// * `seed` - seed of the mnemonic with its passphrase, the fingerprint is computed from it
+    pub fn new(mnemonic: &str, seed: &[u8]) -> Result<BackupSheet, String> {
+        mnemonic_to_entropy(mnemonic)?;
+        let words = normalize_mnemonic(mnemonic).split(' ').enumerate().map(|(position, word)| {
+            let index = mnemonic_lookup(word)?;
+            Ok(BackupWord {
+                number: position + 1,
+                word: String::from(word),
+                prefix: word.chars().take(4).collect(),
+                index,
+                binary: format!("{:011b}", index),
+            })
+        }).collect::<Result<Vec<BackupWord>, String>>()?;
+        let fingerprint = fingerprint(seed)?.iter().map(|byte| format!("{:02x}", byte)).collect();
+        Ok(BackupSheet { checksum_bits: words.len() / 3, words, fingerprint })
+    }
+
+    /// Lay out the page, words are numbered down the first column and continue in the second one
+    fn layout(&self) -> Vec<Element> {
+        let text = |x: f32, y: f32, size: f32, bold: bool, text: String| Element::Text { x, y, size, bold, highlight: false, text };
+        let mut elements = vec![
+            text(MARGIN, MARGIN + 18.0, 18.0, true, String::from("BIP-39 mnemonic backup")),
+            text(MARGIN, MARGIN + 42.0, FONT_SIZE, false, format!("Words: {}   Fingerprint: {}   Checksum bits: {}", self.words.len(), self.fingerprint, self.checksum_bits)),
+        ];
+
+        let top = MARGIN + 64.0;
+        let column_width = (PAGE_WIDTH - 2.0 * MARGIN) / 2.0;
+        let rows = self.words.len().div_ceil(2);
+        let char_width = FONT_SIZE * CHAR_WIDTH;
+        // offsets of number, word, prefix, index and binary in characters
+        let offsets = [1.0, 4.0, 14.0, 20.0, 26.0];
+        for column in 0..2 {
+            let left = MARGIN + column as f32 * column_width;
+            let header = ["#", "Word", "Pref", "Idx", "Binary"];
+            for (offset, label) in offsets.iter().zip(header) {
+                elements.push(text(left + offset * char_width, top - 6.0, FONT_SIZE, true, String::from(label)));
+            }
+            for (row, word) in self.words.iter().skip(column * rows).take(rows).enumerate() {
+                let y = top + row as f32 * ROW_HEIGHT;
+                elements.push(Element::Rect { x: left, y, width: column_width - 8.0, height: ROW_HEIGHT });
+                let baseline = y + ROW_HEIGHT / 2.0 + FONT_SIZE / 3.0;
+                let x = |position: usize| left + offsets[position] * char_width;
+                elements.push(text(x(0), baseline, FONT_SIZE, true, format!("{:02}", word.number)));
+                elements.push(text(x(1), baseline, FONT_SIZE, false, word.word.clone()));
+                elements.push(text(x(2), baseline, FONT_SIZE, true, word.prefix.to_uppercase()));
+                elements.push(text(x(3), baseline, FONT_SIZE, false, format!("{:04}", word.index)));
+                let checksum = if word.number == self.words.len() { self.checksum_bits } else { 0 };
+                let (data, bits) = word.binary.split_at(word.binary.len() - checksum);
+                elements.push(text(x(4), baseline, FONT_SIZE, false, String::from(data)));
+                if !bits.is_empty() {
+                    let x = x(4) + data.len() as f32 * char_width;
+                    elements.push(Element::Text { x, y: baseline, size: FONT_SIZE, bold: true, highlight: true, text: String::from(bits) });
+                }
+            }
+        }
+
+        let bottom = top + rows as f32 * ROW_HEIGHT + 24.0;
+        elements.push(text(MARGIN, bottom, FONT_SIZE, false, String::from("Highlighted bits of the last word are the checksum.")));
+        elements.push(text(MARGIN, bottom + 16.0, FONT_SIZE, false, String::from("Keep this sheet offline, anyone who reads it controls the funds.")));
+        elements
+    }
+
+    /// Render sheet as SVG image
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"210mm\" height=\"297mm\" viewBox=\"0 0 {} {}\">\n<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n",
+            PAGE_WIDTH, PAGE_HEIGHT);
+        for element in self.layout() {
+            match element {
+                Element::Text { x, y, size, bold, highlight, text } => {
+                    let weight = if bold { " font-weight=\"bold\"" } else { "" };
+                    let fill = if highlight { format!("rgb({},{},{})", HIGHLIGHT[0] * 255.0, HIGHLIGHT[1] * 255.0, HIGHLIGHT[2] * 255.0) } else { String::from("#000") };
+                    svg += &format!("<text x=\"{}\" y=\"{}\" font-family=\"Courier, monospace\" font-size=\"{}\"{} fill=\"{}\">{}</text>\n", x, y, size, weight, fill, escape_xml(&text));
+                },
+                Element::Rect { x, y, width, height } => {
+                    svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#000\" stroke-width=\"0.5\"/>\n", x, y, width, height);
+                },
+            }
+        }
+        svg + "</svg>\n"
+    }
+
+    /// Render sheet as single page PDF document using the standard Courier fonts
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut content = String::from("0.5 w\n");
+        for element in self.layout() {
+            match element {
+                Element::Text { x, y, size, bold, highlight, text } => {
+                    let color = if highlight { HIGHLIGHT } else { [0.0; 3] };
+                    content += &format!("BT {} {} {} rg /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                        color[0], color[1], color[2], if bold { "F2" } else { "F1" }, size, x, PAGE_HEIGHT - y, escape_pdf(&text));
+                },
+                Element::Rect { x, y, width, height } => {
+                    content += &format!("{:.2} {:.2} {:.2} {:.2} re S\n", x, PAGE_HEIGHT - y - height, width, height);
+                },
+            }
+        }
+
+        let objects = [
+            String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+            String::from("<< /Type /Pages /Kids [3 0 R] /Count 1 >>"),
+            format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> /Contents 4 0 R >>", PAGE_WIDTH, PAGE_HEIGHT),
+            format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+            String::from("<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>"),
+            String::from("<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold >>"),
+        ];
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (number, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf += &format!("{} 0 obj\n{}\nendobj\n", number + 1, object);
+        }
+        let xref = pdf.len();
+        pdf += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            pdf += &format!("{:010} 00000 n \n", offset);
+        }
+        pdf += &format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref);
+        pdf.into_bytes()
+    }
+}
+
+/// Escape text of SVG element
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Escape PDF string literal
+fn escape_pdf(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('(', "\\(").replace(')', "\\)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mnemonic_to_seed;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn sheet_content() {
+        let sheet = BackupSheet::new(MNEMONIC, &mnemonic_to_seed(MNEMONIC, None)).unwrap();
+        assert_eq!(sheet.fingerprint, "73c5da0a");
+        assert_eq!(sheet.checksum_bits, 4);
+        assert_eq!(sheet.words[11], BackupWord { number: 12, word: String::from("about"), prefix: String::from("abou"), index: 3, binary: String::from("00000000011") });
+        assert!(BackupSheet::new("abandon about", &[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn render_documents() {
+        let sheet = BackupSheet::new(MNEMONIC, &mnemonic_to_seed(MNEMONIC, None)).unwrap();
+        let svg = sheet.to_svg();
+        assert!(svg.contains(">about</text>"));
+        assert!(svg.contains("fill=\"rgb(204,0,0)\">0011</text>"));
+        assert!(svg.contains("73c5da0a"));
+
+        let pdf = String::from_utf8(sheet.to_pdf()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n") && pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(about) Tj"));
+        let xref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[xref..].starts_with("xref\n0 7\n"));
+        let offset: usize = pdf[xref..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with("1 0 obj"));
+    }
+}
@@ -0,0 +1,381 @@
+//! Processing of many records read from a file or stdin in parallel
+
+use crate::output::{mnemonic_error, ErrorCode, Format, Output, Report, Verbosity, View};
+use crate::{add_mnemonic_details, check_valid_check_params, check_valid_entropy, check_valid_mnemonic, convert_entropy, to_hex_string, trim_input};
+use crate::util::decode_hex;
+use mnemonic::quality::{analyze_entropy, Severity};
+use mnemonic::{fingerprint, mnemonic_to_entropy, normalize_mnemonic};
+use mnemonic::strength::check_passphrase;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+
+/// Operation applied to every record
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Operation {
+    /// Convert entropy to mnemonic
+    ToMnemonic,
+    /// Convert mnemonic to entropy
+    ToEntropy,
+    /// Compute seed of mnemonic
+    ToSeed,
+    /// Check if mnemonic generates given seed
+    Verify,
+}
+
+impl Operation {
+    /// Operation name reported in JSON
+    fn name(self) -> &'static str {
+        match self {
+            Operation::ToMnemonic => "to-mnemonic",
+            Operation::ToEntropy => "to-entropy",
+            Operation::ToSeed => "to-seed",
+            Operation::Verify => "verify",
+        }
+    }
+
+    /// Name of CSV column or JSON key with the input value
+    fn input_key(self) -> &'static str {
+        match self {
+            Operation::ToMnemonic => "entropy",
+            _ => "mnemonic",
+        }
+    }
+
+    /// Whether the seed, and so the passphrase, is needed
+    fn needs_passphrase(self) -> bool {
+        matches!(self, Operation::ToSeed | Operation::Verify)
+    }
+}
+
+/// Format of records
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum InputFormat {
+    /// One value per line
+    Lines,
+    /// CSV with header naming the `entropy` or `mnemonic`, `seed` and `passphrase` columns
+    Csv,
+    /// One JSON object per line with the same keys as CSV columns
+    Jsonl,
+}
+
+/// Single input record
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    line: usize,
+    value: String,
+    seed: Option<String>,
+    passphrase: Option<String>,
+}
+
+/// Record which could not be parsed, with its line number and reason
+type RecordError = (usize, String);
+
+/// Parse records of input, blank lines are skipped
+///
+/// # Arguments
+///
+/// * `input` - content of the input
+/// * `format` - format of records
+/// * `operation` - operation determining the required fields
+pub fn parse_records(input: &str, format: InputFormat, operation: Operation) -> Result<Vec<Result<Record, RecordError>>, String> {
+    match format {
+        InputFormat::Lines => {
+            if operation == Operation::Verify {
+                return Err(String::from("Verify requires seed of each record, use csv or jsonl input format"));
+            }
+            Ok(input.lines().enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| Ok(Record { line: index + 1, value: String::from(line), seed: None, passphrase: None }))
+                .collect())
+        },
+        InputFormat::Csv => parse_csv(input, operation),
+        InputFormat::Jsonl => Ok(input.lines().enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| parse_json_record(index + 1, line, operation))
+            .collect()),
+    }
+}
+
+fn parse_csv(input: &str, operation: Operation) -> Result<Vec<Result<Record, RecordError>>, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input.as_bytes());
+    let headers = reader.headers().map_err(|error| format!("CSV header could not be read: {}", error))?.clone();
+    let column = |name: &str| headers.iter().position(|header| header.trim() == name);
+    let value_column = column(operation.input_key()).ok_or_else(|| format!("CSV header has no {} column", operation.input_key()))?;
+    let seed_column = column("seed");
+    if operation == Operation::Verify && seed_column.is_none() {
+        return Err(String::from("CSV header has no seed column"));
+    }
+    let passphrase_column = column("passphrase");
+
+    Ok(reader.records().map(|record| {
+        let record = record.map_err(|error| (error.position().map_or(0, |position| position.line() as usize), error.to_string()))?;
+        let line = record.position().map_or(0, |position| position.line() as usize);
+        let field = |column: Option<usize>| column.and_then(|column| record.get(column)).map(String::from);
+        let value = field(Some(value_column)).ok_or_else(|| (line, format!("Missing {} field", operation.input_key())))?;
+        Ok(Record { line, value, seed: field(seed_column), passphrase: field(passphrase_column) })
+    }).collect())
+}
+
+fn parse_json_record(line: usize, content: &str, operation: Operation) -> Result<Record, RecordError> {
+    let object: Value = serde_json::from_str(content).map_err(|error| (line, format!("Invalid JSON: {}", error)))?;
+    let field = |key: &str| -> Result<Option<String>, RecordError> {
+        match object.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err((line, format!("Field {} is not a string", key))),
+        }
+    };
+    let value = field(operation.input_key())?.ok_or_else(|| (line, format!("Missing {} field", operation.input_key())))?;
+    Ok(Record { line, value, seed: field("seed")?, passphrase: field("passphrase")? })
+}
+
+/// Apply operation to record, returns report and whether the record passed
+///
+/// Conversions add fingerprint only if the passphrase of the record is known, since it is not prompted for them.
+fn process(operation: Operation, record: &Record, binary: bool, passphrase: Option<&str>, source: &str, output: &Output) -> Result<(Report, bool), (ErrorCode, String)> {
+    let (seed_params, view) = (&output.seed_params, &output.view);
+    let mut value = record.value.clone();
+    trim_input(&mut value);
+    if operation.input_key() == "mnemonic" {
+        value = normalize_mnemonic(&value);
+    }
+    let (passphrase, source) = match &record.passphrase {
+        Some(own) => (Some(own.as_str()), "record"),
+        None => (passphrase, source),
+    };
+    let known = passphrase.is_some();
+    let passphrase = passphrase.unwrap_or_default();
+    if operation.needs_passphrase() {
+        let findings = check_passphrase(passphrase, &output.passphrase_policy);
+        let violations: Vec<&str> = findings.iter().filter(|finding| finding.severity == Severity::Critical).map(|finding| finding.message.as_str()).collect();
+        if !violations.is_empty() {
+            return Err((ErrorCode::InvalidPassphrase, format!("Passphrase does not satisfy policy: {}", violations.join(", "))));
+        }
+    }
+    let mut report = Report::new(operation.name());
+    report.add_json("line", Value::from(record.line));
+
+    match operation {
+        Operation::ToMnemonic => {
+            if !check_valid_entropy(&value, binary) {
+                return Err((ErrorCode::InvalidFormat, String::from("Entropy parameter invalid format, only hexadecimal or binary format accepted")));
+            }
+            let (entropy, mnemonic) = convert_entropy(&value, binary).map_err(|error| (ErrorCode::InvalidEntropy, format!("Input error: {}", error)))?;
+            let findings = analyze_entropy(&decode_hex(&entropy).expect("entropy was converted"));
+            if !output.allow_weak && findings.iter().any(|finding| finding.severity == Severity::Critical) {
+                return Err((ErrorCode::WeakEntropy, String::from("Input error: Entropy is weak, use --allow-weak to accept it")));
+            }
+            if !findings.is_empty() {
+                report.add_json("findings", Value::from(findings.iter().map(|finding| json!({
+                    "severity": finding.severity.as_str(),
+                    "check": finding.check,
+                    "message": finding.message,
+                })).collect::<Vec<_>>()));
+            }
+            report.add("Entered entropy", "entropy", &entropy);
+            report.add_result("Output mnemonic", "mnemonic", &mnemonic);
+            if known {
+                add_conversion_fingerprint(&mut report, &seed_params.mnemonic_to_seed(&mnemonic, Some(passphrase)), source)?;
+            }
+            add_mnemonic_details(&mut report, &mnemonic);
+            report.add_mnemonic_info(&mnemonic);
+        },
+        Operation::ToEntropy | Operation::ToSeed => {
+            if !check_valid_mnemonic(&value) {
+                return Err((ErrorCode::InvalidFormat, String::from("Mnemonic parameter invalid format, only alphabetic and whitespace characters accepted")));
+            }
+            let entropy = mnemonic_to_entropy(&value).map_err(|error| mnemonic_error(&value, &error, view.verbosity == Verbosity::Verbose))?;
+            report.add("Entered mnemonic phrase", "mnemonic", &value);
+            if operation == Operation::ToEntropy {
+                report.add_result("Initial entropy", "entropy", &to_hex_string(entropy));
+                if known {
+                    add_conversion_fingerprint(&mut report, &seed_params.mnemonic_to_seed(&value, Some(passphrase)), source)?;
+                }
+            } else {
+                let seed = seed_params.mnemonic_to_seed(&value, Some(passphrase));
+                report.add_result("Output seed", "seed", &to_hex_string(seed.clone()));
+                add_fingerprint(&mut report, &seed)?;
+            }
+            add_mnemonic_details(&mut report, &value);
+            report.add_mnemonic_info(&value);
+        },
+        Operation::Verify => {
+            let mut seed = record.seed.clone().unwrap_or_default();
+            trim_input(&mut seed);
+            if !check_valid_check_params(&value, &seed) {
+                return Err((ErrorCode::InvalidFormat, String::from("Check parameters invalid format")));
+            }
+            let expected_seed = decode_hex(&seed).map_err(|_| (ErrorCode::InvalidSeed, String::from("Input error: Cannot decode hex!")))?;
+            let computed_seed = seed_params.mnemonic_to_seed(&value, Some(passphrase));
+            let verified = computed_seed == expected_seed;
+            report.add("Input mnemonic", "mnemonic", &value);
+            report.add("Input seed", "seed", &seed);
+            report.add("Output seed", "computed_seed", &to_hex_string(computed_seed.clone()));
+            add_fingerprint(&mut report, &computed_seed)?;
+            add_mnemonic_details(&mut report, &value);
+            report.add_status(String::from(if verified { "OK" } else { "NOK" }));
+            report.add_json("verified", Value::from(verified));
+            report.add_mnemonic_info(&value);
+            return Ok((report, verified));
+        },
+    }
+    Ok((report, true))
+}
+
+/// Add fingerprint of seed to report of record
+fn add_fingerprint(report: &mut Report, seed: &[u8]) -> Result<(), (ErrorCode, String)> {
+    let fingerprint = fingerprint(seed).map_err(|error| (ErrorCode::InvalidDerivation, format!("Input error: {}", error)))?;
+    report.add("Fingerprint", "fingerprint", &to_hex_string(fingerprint.to_vec()));
+    Ok(())
+}
+
+/// Add fingerprint of seed of converted record and source of its passphrase to report
+fn add_conversion_fingerprint(report: &mut Report, seed: &[u8], source: &str) -> Result<(), (ErrorCode, String)> {
+    report.add("Fingerprint passphrase", "fingerprint_passphrase", source);
+    add_fingerprint(report, seed)
+}
+
+/// Render result of one record
+fn render_result(format: Format, view: &View, line: usize, result: &Result<(Report, bool), (ErrorCode, String)>) -> String {
+    match (format, result) {
+        (Format::Text, Ok((report, _))) => report.render_plain(view).lines().map(|result| format!("{}: {}\n", line, result)).collect(),
+        (Format::Json, Ok((report, _))) => report.render(Format::Json, view),
+        (Format::Text, Err((_, message))) => format!("{}: error: {}\n", line, message),
+        (Format::Json, Err((code, message))) => format!("{}\n", json!({ "line": line, "error": { "code": code.as_str(), "message": message } })),
+    }
+}
+
+/// Process all records in parallel and write their results in input order, followed by summary on stderr
+///
+/// # Arguments
+///
+/// * `output` - where and how to write the results
+/// * `operation` - operation applied to every record
+/// * `records` - parsed records
+/// * `binary` - interpret entropy as binary string
+/// * `passphrase` - passphrase of records without their own one, None if it was not loaded
+/// * `source` - name of source of the passphrase
+///
+/// Returns number of records which failed or did not verify.
+pub fn run(output: &Output, operation: Operation, records: &[Result<Record, RecordError>], binary: bool, passphrase: Option<&str>, source: &str) -> Result<usize, std::io::Error> {
+    let results: Vec<(String, bool)> = records.par_iter().map(|record| match record {
+        Ok(record) => {
+            let result = process(operation, record, binary, passphrase, source, output);
+            let passed = matches!(result, Ok((_, true)));
+            (render_result(output.format, &output.view, record.line, &result), passed)
+        },
+        Err((line, message)) => (render_result(output.format, &output.view, *line, &Err((ErrorCode::InvalidFormat, message.clone()))), false),
+    }).collect();
+
+    let failed = results.iter().filter(|(_, passed)| !passed).count();
+    output.write_rendered(&results.into_iter().map(|(rendered, _)| rendered).collect::<String>())?;
+
+    let succeeded = records.len() - failed;
+    match output.format {
+        Format::Text => eprintln!("Processed {} records: {} succeeded, {} failed", records.len(), succeeded, failed),
+        Format::Json => eprintln!("{}", json!({ "summary": { "records": records.len(), "succeeded": succeeded, "failed": failed } })),
+    }
+    Ok(failed)
+}
+
+/// Whether passphrase has to be loaded for some record
+///
+/// # Arguments
+///
+/// * `operation` - operation applied to every record
+/// * `records` - parsed records
+/// * `prompt` - passphrase would be prompted for, which conversions do not need
+pub fn needs_passphrase(operation: Operation, records: &[Result<Record, RecordError>], prompt: bool) -> bool {
+    (operation.needs_passphrase() || !prompt) && records.iter().any(|record| matches!(record, Ok(record) if record.passphrase.is_none()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mnemonic::seed::SeedDerivationParams;
+    use mnemonic::strength::{Charset, Policy};
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const SEED: &str = "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04";
+
+    #[test]
+    fn parse_formats() {
+        let records = parse_records("00000000000000000000000000000000\n\nzz\n", InputFormat::Lines, Operation::ToMnemonic).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(&records[1], Ok(Record { line: 3, .. })));
+        assert!(parse_records("x\n", InputFormat::Lines, Operation::Verify).is_err());
+
+        let csv = format!("mnemonic,seed,passphrase\n{},{},TREZOR\n\"{}\"\n", MNEMONIC, SEED, MNEMONIC);
+        let records = parse_records(&csv, InputFormat::Csv, Operation::Verify).unwrap();
+        assert_eq!(records[0], Ok(Record { line: 2, value: String::from(MNEMONIC), seed: Some(String::from(SEED)), passphrase: Some(String::from("TREZOR")) }));
+        assert_eq!(records[1], Ok(Record { line: 3, value: String::from(MNEMONIC), seed: None, passphrase: None }));
+        assert!(parse_records("entropy\n00\n", InputFormat::Csv, Operation::ToSeed).is_err());
+
+        let jsonl = format!("{{\"mnemonic\":\"{}\"}}\n{{\"mnemonic\":1}}\nnot json\n", MNEMONIC);
+        let records = parse_records(&jsonl, InputFormat::Jsonl, Operation::ToSeed).unwrap();
+        assert!(records[0].is_ok());
+        assert!(matches!(&records[1], Err((2, _))));
+        assert!(matches!(&records[2], Err((3, _))));
+    }
+
+    fn output(passphrase_policy: Policy) -> Output {
+        Output {
+            to_file: None,
+            format: Format::Text,
+            plain: true,
+            force: false,
+            password: None,
+            view: View::default(),
+            expected_fingerprint: None,
+            allow_weak: false,
+            passphrase_policy,
+            seed_params: SeedDerivationParams::default(),
+        }
+    }
+
+    #[test]
+    fn process_records() {
+        let record = |value: &str, seed: Option<&str>, passphrase: Option<&str>| Record { line: 1, value: String::from(value), seed: seed.map(String::from), passphrase: passphrase.map(String::from) };
+        let output = output(Policy::default());
+        let view = View::default();
+        let (report, passed) = process(Operation::Verify, &record(MNEMONIC, Some(SEED), Some("TREZOR")), false, None, "prompt", &output).unwrap();
+        assert!(passed);
+        assert_eq!(report.render_plain(&view), "OK\n");
+        assert!(!process(Operation::Verify, &record(MNEMONIC, Some(SEED), None), false, None, "prompt", &output).unwrap().1);
+        let (report, _) = process(Operation::ToSeed, &record(MNEMONIC, None, None), false, Some("TREZOR"), "file", &output).unwrap();
+        assert_eq!(report.render_plain(&view), format!("{}\n", SEED));
+        let error = process(Operation::ToEntropy, &record("abandon abandon", None, None), false, None, "prompt", &output).unwrap_err();
+        assert_eq!(error.0, ErrorCode::InvalidMnemonic);
+        assert_eq!(render_result(Format::Text, &view, 7, &Err(error)), "7: error: Input error: Mnemonic sentence could contain just 12, 15, 18, 21 or 24 words!\n");
+    }
+
+    #[test]
+    fn passphrase_policy_of_records() {
+        let record = |passphrase: &str| Record { line: 1, value: String::from(MNEMONIC), seed: Some(String::from(SEED)), passphrase: Some(String::from(passphrase)) };
+        let output = output(Policy { min_bits: 0.0, reject_whitespace: true, charset: Charset::Any });
+        assert!(process(Operation::Verify, &record("TREZOR"), false, None, "prompt", &output).unwrap().1);
+        let error = process(Operation::Verify, &record("TREZOR "), false, None, "prompt", &output).unwrap_err();
+        assert_eq!(error.0, ErrorCode::InvalidPassphrase);
+        assert!(error.1.starts_with("Passphrase does not satisfy policy: "));
+        assert!(process(Operation::ToSeed, &record("TREZOR "), false, None, "prompt", &output).is_err());
+        // passphrase is not used by conversions
+        assert!(process(Operation::ToEntropy, &record("TREZOR "), false, None, "prompt", &output).is_ok());
+    }
+
+    #[test]
+    fn fingerprint_of_conversions() {
+        let record = |passphrase: Option<&str>| Record { line: 1, value: String::from(MNEMONIC), seed: None, passphrase: passphrase.map(String::from) };
+        let output = output(Policy::default());
+        let json = |passphrase: Option<&str>, own: Option<&str>| {
+            process(Operation::ToEntropy, &record(own), false, passphrase, "environment variable", &output).unwrap().0.render(Format::Json, &View::default())
+        };
+        assert!(!json(None, None).contains("fingerprint"));
+        assert!(json(Some(""), None).contains("\"fingerprint\":\"73c5da0a\""));
+        assert!(json(Some(""), None).contains("\"fingerprint_passphrase\":\"environment variable\""));
+        assert!(json(None, Some("")).contains("\"fingerprint_passphrase\":\"record\""));
+        let records = [Ok(record(None))];
+        assert!(!needs_passphrase(Operation::ToEntropy, &records, true));
+        assert!(needs_passphrase(Operation::ToEntropy, &records, false));
+        assert!(needs_passphrase(Operation::ToSeed, &records, true));
+    }
+}
@@ -0,0 +1,150 @@
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, ProjectivePoint, Scalar};
+use crate::hmac_sha512;
+
+/// Offset of hardened child indices
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// BIP-32 extended private key on secp256k1
+#[derive(Clone, PartialEq)]
+pub struct ExtendedPrivateKey {
+    private_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+/// Convert 32 bytes to scalar, fails if the value is not lower than the curve order
+fn to_scalar(bytes: &[u8]) -> Option<Scalar> {
+    Option::from(Scalar::from_repr(FieldBytes::clone_from_slice(bytes)))
+}
+
+/// Compute compressed public key of private key
+pub fn public_key(private_key: &[u8; 32]) -> Result<[u8; 33], String> {
+    let scalar = match to_scalar(private_key) {
+        Some(scalar) if !bool::from(scalar.is_zero()) => scalar,
+        _ => return Err(String::from("Invalid private key!")),
+    };
+    let point = (ProjectivePoint::GENERATOR * scalar).to_affine();
+    let mut result = [0u8; 33];
+    result.copy_from_slice(point.to_encoded_point(true).as_bytes());
+    Ok(result)
+}
+
+/// Parse derivation path such as m/84'/0'/0'/0, hardened indices are marked by ' or h
+pub fn parse_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut parts = path.trim().split('/');
+    if parts.next() != Some("m") {
+        return Err(format!("Derivation path has to start with m: {}", path));
+    }
+    parts
+        .map(|part| {
+            let (number, hardened) = match part.strip_suffix('\'').or_else(|| part.strip_suffix('h')) {
+                Some(number) => (number, true),
+                None => (part, false),
+            };
+            match number.parse::<u32>() {
+                Ok(index) if index < HARDENED => Ok(if hardened { index + HARDENED } else { index }),
+                _ => Err(format!("Invalid derivation path index: {}", part)),
+            }
+        })
+        .collect()
+}
+
+/// Format derivation path indices as string
+pub fn format_path(indices: &[u32]) -> String {
+    let mut result = String::from("m");
+    for &index in indices {
+        if index >= HARDENED {
+            result.push_str(&format!("/{}'", index - HARDENED));
+        } else {
+            result.push_str(&format!("/{}", index));
+        }
+    }
+    result
+}
+
+impl ExtendedPrivateKey {
+    /// Create master key from seed
+    pub fn new_master(seed: &[u8]) -> Result<ExtendedPrivateKey, String> {
+        ExtendedPrivateKey::from_hmac(&hmac_sha512(seed, b"Bitcoin seed"))
+    }
+
+    /// Split HMAC output into private key and chain code
+    fn from_hmac(output: &[u8]) -> Result<ExtendedPrivateKey, String> {
+        let mut key = ExtendedPrivateKey { private_key: [0u8; 32], chain_code: [0u8; 32] };
+        key.private_key.copy_from_slice(&output[..32]);
+        key.chain_code.copy_from_slice(&output[32..]);
+        public_key(&key.private_key)?;
+        Ok(key)
+    }
+
+    /// Derive child key with given index
+    pub fn derive_child(&self, index: u32) -> Result<ExtendedPrivateKey, String> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED {
+            data.push(0);
+            data.extend_from_slice(&self.private_key);
+        } else {
+            data.extend_from_slice(&self.public_key());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+        let output = hmac_sha512(&data, &self.chain_code);
+
+        let tweak = to_scalar(&output[..32]).ok_or_else(|| String::from("Invalid child key, use next index!"))?;
+        let child = tweak + to_scalar(&self.private_key).unwrap();
+        let mut key = output;
+        key[..32].copy_from_slice(&child.to_bytes());
+        ExtendedPrivateKey::from_hmac(&key)
+    }
+
+    /// Derive key along the path given by indices
+    pub fn derive_path(&self, indices: &[u32]) -> Result<ExtendedPrivateKey, String> {
+        let mut key = self.clone();
+        for &index in indices {
+            key = key.derive_child(index)?;
+        }
+        Ok(key)
+    }
+
+    /// Private key
+    pub fn private_key(&self) -> &[u8; 32] {
+        &self.private_key
+    }
+
+    /// Chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Compressed public key
+    pub fn public_key(&self) -> [u8; 33] {
+        public_key(&self.private_key).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::decode_hex;
+
+    #[test]
+    fn bip32_tv1() {
+        let master = ExtendedPrivateKey::new_master(&decode_hex("000102030405060708090a0b0c0d0e0f").unwrap()).unwrap();
+        assert_eq!(master.private_key().to_vec(), decode_hex("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35").unwrap());
+        assert_eq!(master.chain_code().to_vec(), decode_hex("873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508").unwrap());
+
+        let child = master.derive_path(&parse_path("m/0'/1/2'/2/1000000000").unwrap()).unwrap();
+        assert_eq!(child.private_key().to_vec(), decode_hex("471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8").unwrap());
+        assert_eq!(child.public_key().to_vec(), decode_hex("022a471424da5e657499d1ff51cb43c47481a03b1e77f951fe64cec9f5a48f7011").unwrap());
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(parse_path("m/84'/0h/0'/0/5").unwrap(), vec![84 + HARDENED, HARDENED, HARDENED, 0, 5]);
+        assert_eq!(format_path(&parse_path("m/84h/0'/1").unwrap()), "m/84'/0'/1");
+        assert_eq!(parse_path("m").unwrap(), vec![]);
+        assert!(parse_path("84'/0'").is_err());
+        assert!(parse_path("m/x").is_err());
+        assert!(parse_path("m/2147483648").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{hmac_sha256, hmac_sha512, mnemonic_to_entropy, mnemonic_to_seed, pbkdf2};
+
+/// Length of BIP32-Ed25519 extended root key (kL || kR || chain code)
+pub const ROOT_KEY_SIZE: usize = 96;
+
+const ICARUS_ITERATIONS: usize = 4096;
+const LEDGER_HMAC_KEY: &[u8] = b"ed25519 seed";
+
+/// Master key generation scheme used by Cardano wallets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    /// Yoroi, Daedalus (Shelley) and most software wallets
+    Icarus,
+    /// Ledger hardware wallets
+    Ledger,
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Scheme, String> {
+        match name {
+            "icarus" => Ok(Scheme::Icarus),
+            "ledger" => Ok(Scheme::Ledger),
+            _ => Err(format!("Unknown Cardano scheme: {}", name)),
+        }
+    }
+}
+
+/// Derive BIP32-Ed25519 extended root key from mnemonic
+///
+/// # Arguments
+///
+/// * `scheme` - master key generation scheme
+/// * `mnemonic` - the mnemonic
+/// * `passphrase` - an optional passphrase
+pub fn root_key(scheme: Scheme, mnemonic: &str, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    match scheme {
+        Scheme::Icarus => icarus_root_key(mnemonic, passphrase),
+        Scheme::Ledger => ledger_root_key(mnemonic, passphrase),
+    }
+}
+
+/// Icarus root key, PBKDF2 of the entropy with passphrase as password
+pub fn icarus_root_key(mnemonic: &str, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    let entropy = mnemonic_to_entropy(mnemonic)?;
+    let passphrase = passphrase.unwrap_or("");
+    let mut key = pbkdf2(passphrase.as_bytes(), &entropy, ICARUS_ITERATIONS, ROOT_KEY_SIZE);
+    key[0] &= 0b1111_1000;
+    key[31] &= 0b0001_1111;
+    key[31] |= 0b0100_0000;
+    Ok(key)
+}
+
+/// Ledger root key, HMAC-SHA512 of the BIP-39 seed repeated until the third highest bit of kL is clear
+pub fn ledger_root_key(mnemonic: &str, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    mnemonic_to_entropy(mnemonic)?; // reject invalid mnemonic
+    let seed = mnemonic_to_seed(mnemonic, passphrase);
+
+    let mut chain_code_data = vec![1u8];
+    chain_code_data.extend_from_slice(&seed);
+    let chain_code = hmac_sha256(&chain_code_data, LEDGER_HMAC_KEY);
+
+    let mut key = hmac_sha512(&seed, LEDGER_HMAC_KEY);
+    while key[31] & 0b0010_0000 != 0 {
+        key = hmac_sha512(&key, LEDGER_HMAC_KEY);
+    }
+    key[0] &= 0b1111_1000;
+    key[31] &= 0b0111_1111;
+    key[31] |= 0b0100_0000;
+    key.extend_from_slice(&chain_code);
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::decode_hex;
+
+    const ICARUS_MNEMONIC: &str = "eight country switch draw meat scout mystery blade tip drift useless good keep usage title";
+
+    #[test]
+    fn icarus_tv1() {
+        let expected = "c065afd2832cd8b087c4d9ab7011f481ee1e0721e78ea5dd609f3ab3f156d245d176bd8fd4ec60b4731c3918a2a72a0226c0cd119ec35b47e4d55884667f552a23f7fdcd4a10c6cd2c7393ac61d877873e248f417634aa3d812af327ffe9d620";
+        assert_eq!(icarus_root_key(ICARUS_MNEMONIC, None).unwrap(), decode_hex(expected).unwrap());
+    }
+
+    #[test]
+    fn icarus_tv2() {
+        let expected = "70531039904019351e1afb361cd1b312a4d0565d4ff9f8062d38acf4b15cce41d7b5738d9c893feea55512a3004acb0d222c35d3e3d5cde943a15a9824cbac59443cf67e589614076ba01e354b1a432e0e6db3b59e37fc56b5fb0222970a010e";
+        assert_eq!(icarus_root_key(ICARUS_MNEMONIC, Some("foo")).unwrap(), decode_hex(expected).unwrap());
+    }
+
+    #[test]
+    fn ledger_tv1() {
+        let mnemonic = "recall grace sport punch exhibit mad harbor stand obey short width stem awkward used stairs wool ugly trap season stove worth toward congress jaguar";
+        let expected = "a08cf85b564ecf3b947d8d4321fb96d70ee7bb760877e371899b14e2ccf88658104b884682b57efd97decbb318a45c05a527b9cc5c2f64f7352935a049ceea60680d52308194ccef2a18e6812b452a5815fbd7f5babc083856919aaf668fe7e4";
+        assert_eq!(ledger_root_key(mnemonic, None).unwrap(), decode_hex(expected).unwrap());
+    }
+
+    #[test]
+    fn invalid_mnemonic() {
+        assert!(root_key(Scheme::Icarus, "eight country switch", None).is_err());
+        assert!(root_key(Scheme::Ledger, "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon", None).is_err());
+    }
+}
@@ -0,0 +1,408 @@
+use clap::builder::{PossibleValuesParser, RangedU64ValueParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand};
+use clap_complete::Shell;
+use crate::batch::{InputFormat, Operation};
+use crate::output::{Format, Layout, Verbosity, View, EXIT_CODES};
+use crate::passphrase::Source;
+use mnemonic::seedqr::SeedQrFormat;
+use mnemonic::seed::{Prf, SeedDerivationParams, SeedPreset};
+use mnemonic::strength::{Charset, Policy};
+
+/// BIP-39 mnemonic, entropy and seed tool
+#[derive(Parser)]
+#[command(name = "mnemonic", version, about, after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(flatten)]
+    pub io: IoArgs,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    #[command(flatten)]
+    pub seed: SeedArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Input and output options shared by all subcommands
+#[derive(Args)]
+pub struct IoArgs {
+    /// Write output to file instead of stdout
+    #[arg(long, global = true, value_name = "FILE")]
+    pub to_file: Option<String>,
+
+    /// Overwrite existing output file
+    #[arg(long, global = true)]
+    pub force: bool,
+
+    /// Interpret positional arguments as paths of files to load values from, encrypted files are decrypted
+    #[arg(long, global = true)]
+    pub from_file: bool,
+
+    /// Encrypt output file with password
+    #[arg(long, global = true, requires = "to_file")]
+    pub encrypt: bool,
+
+    /// Read password of encrypted files from first line of file
+    #[arg(long, global = true, value_name = "FILE")]
+    pub password_file: Option<String>,
+
+    /// Read password of encrypted files from environment variable
+    #[arg(long, global = true, value_name = "VAR")]
+    pub password_env: Option<String>,
+
+    /// Output format, json prints one object per operation and errors as JSON on stderr
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Print only the requested value, verification reports only by exit code
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print also intermediate values, e.g. word indices and checksum bits
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Mask secrets except their first and last characters
+    #[arg(long, global = true)]
+    pub redact: bool,
+
+    /// Layout of mnemonics in text output, JSON always holds single space separated words
+    #[arg(long, global = true, value_enum, default_value_t = Layout::SingleLine)]
+    pub layout: Layout,
+
+    /// Separator of words of single line mnemonics in text output
+    #[arg(long, global = true, value_name = "SEP", default_value = " ")]
+    pub separator: String,
+
+    /// Fail with exit code 6 unless the seed has this BIP-32 master key fingerprint
+    #[arg(long, global = true, value_name = "HEX", value_parser = parse_fingerprint)]
+    pub expect_fingerprint: Option<String>,
+
+    /// Create mnemonic even from entropy with critical quality findings, e.g. repeated pattern
+    #[arg(long, global = true)]
+    pub allow_weak: bool,
+}
+
+/// Parse fingerprint of 4 bytes in hexadecimal
+pub fn parse_fingerprint(fingerprint: &str) -> Result<String, String> {
+    if fingerprint.len() != 8 || !fingerprint.chars().all(|character| character.is_ascii_hexdigit()) {
+        return Err(String::from("Fingerprint has to be 8 hexadecimal characters"));
+    }
+    Ok(fingerprint.to_lowercase())
+}
+
+impl IoArgs {
+    /// Selected verbosity and redaction of output
+    pub fn view(&self) -> View {
+        let verbosity = match (self.quiet, self.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (_, true) => Verbosity::Verbose,
+            _ => Verbosity::Normal,
+        };
+        View { verbosity, redact: self.redact, layout: self.layout, separator: self.separator.clone() }
+    }
+
+    /// Selected password source, error if more than one was given
+    pub fn password_source(&self) -> Result<Source, String> {
+        Source::from_options(self.password_file.clone(), self.password_env.clone(), None, false)
+            .map_err(|_| String::from("Only one password source can be given"))
+    }
+}
+
+/// Sources of the passphrase, it is prompted for on the terminal if none is given
+#[derive(Args)]
+pub struct PassphraseArgs {
+    /// Read passphrase from first line of file
+    #[arg(long, global = true, value_name = "FILE")]
+    pub passphrase_file: Option<String>,
+
+    /// Read passphrase from environment variable
+    #[arg(long, global = true, value_name = "VAR")]
+    pub passphrase_env: Option<String>,
+
+    /// Read passphrase from first line of open file descriptor
+    #[arg(long, global = true, value_name = "N")]
+    pub passphrase_fd: Option<u32>,
+
+    /// Use empty passphrase without prompting
+    #[arg(long, global = true)]
+    pub no_passphrase: bool,
+
+    /// Reject passphrase with lower estimated strength
+    #[arg(long, global = true, value_name = "BITS", default_value_t = 0.0)]
+    pub min_passphrase_bits: f64,
+
+    /// Characters allowed in passphrase, ascii allows printable ASCII, nfkd allows Unicode unchanged by normalization
+    #[arg(long, global = true, default_value = "any", value_parser = PossibleValuesParser::new(["any", "ascii", "nfkd"]).map(|charset| charset.parse::<Charset>().unwrap()))]
+    pub passphrase_charset: Charset,
+
+    /// Reject passphrase with leading or trailing whitespace
+    #[arg(long, global = true)]
+    pub reject_passphrase_whitespace: bool,
+}
+
+impl PassphraseArgs {
+    /// Selected passphrase source, error if more than one was given
+    pub fn source(&self) -> Result<Source, String> {
+        Source::from_options(self.passphrase_file.clone(), self.passphrase_env.clone(), self.passphrase_fd, self.no_passphrase)
+    }
+
+    /// Requirements the passphrase has to satisfy
+    pub fn policy(&self) -> Policy {
+        Policy { min_bits: self.min_passphrase_bits, reject_whitespace: self.reject_passphrase_whitespace, charset: self.passphrase_charset }
+    }
+}
+
+/// Parameters of seed derivation for a wallet which does not follow BIP-39
+#[derive(Args)]
+pub struct SeedArgs {
+    /// Seed derivation of wallet, BIP-39 or Electrum 2.0 native seed phrases with salt "electrum", the other options override it
+    #[arg(long, global = true, default_value = "standard", value_parser = PossibleValuesParser::new(["standard", "electrum"]).map(|preset| preset.parse::<SeedPreset>().unwrap()))]
+    pub seed_preset: SeedPreset,
+
+    /// Salt preceding the passphrase in PBKDF2
+    #[arg(long, global = true, value_name = "PREFIX")]
+    pub salt_prefix: Option<String>,
+
+    /// Number of PBKDF2 iterations
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: Option<u32>,
+
+    /// Pseudorandom function of PBKDF2
+    #[arg(long, global = true, value_parser = PossibleValuesParser::new(["hmac-sha512", "hmac-sha256"]).map(|prf| prf.parse::<Prf>().unwrap()))]
+    pub prf: Option<Prf>,
+
+    /// Length of the seed in bytes, BIP-32 accepts 16 to 64 bytes
+    #[arg(long, global = true, value_name = "BYTES", value_parser = RangedU64ValueParser::<usize>::new().range(16..=64))]
+    pub seed_length: Option<usize>,
+}
+
+impl SeedArgs {
+    /// Selected preset with overridden parameters
+    pub fn params(&self) -> SeedDerivationParams {
+        seed_params(self.seed_preset, self.salt_prefix.as_deref(), self.iterations, self.prf, self.seed_length)
+    }
+}
+
+/// Parameters of preset with the given ones overridden
+pub fn seed_params(preset: SeedPreset, salt_prefix: Option<&str>, iterations: Option<u32>, prf: Option<Prf>, seed_length: Option<usize>) -> SeedDerivationParams {
+    let mut params = SeedDerivationParams::from(preset);
+    if let Some(salt_prefix) = salt_prefix {
+        params = params.salt_prefix(salt_prefix);
+    }
+    if let Some(iterations) = iterations {
+        params = params.iterations(iterations);
+    }
+    if let Some(prf) = prf {
+        params = params.prf(prf);
+    }
+    if let Some(seed_length) = seed_length {
+        params = params.out_len(seed_length);
+    }
+    params
+}
+
+/// Image formats of SeedQR codes
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum QrImage {
+    /// Unicode blocks with ANSI colors
+    Terminal,
+    /// Grayscale PNG image
+    Png,
+    /// SVG image
+    Svg,
+}
+
+/// Document formats of backup sheets
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SheetFormat {
+    /// Single page A4 PDF document
+    Pdf,
+    /// A4 SVG image
+    Svg,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generate new mnemonic from system randomness
+    Generate {
+        /// Number of words of the mnemonic
+        #[arg(long, default_value_t = 24, value_parser = PossibleValuesParser::new(["12", "15", "18", "21", "24"]).map(|words| words.parse::<usize>().unwrap()))]
+        words: usize,
+    },
+
+    /// Convert entropy to mnemonic
+    ToMnemonic {
+        /// Entropy in hexadecimal (or binary with --binary)
+        entropy: String,
+
+        /// Interpret entropy as binary string
+        #[arg(long)]
+        binary: bool,
+    },
+
+    /// Convert mnemonic to entropy
+    ToEntropy {
+        /// Mnemonic sentence
+        mnemonic: String,
+    },
+
+    /// Compute seed of mnemonic
+    ToSeed {
+        /// Mnemonic sentence
+        mnemonic: String,
+    },
+
+    /// Check if mnemonic generates given seed
+    Verify {
+        /// Mnemonic sentence
+        mnemonic: String,
+
+        /// Expected seed in hexadecimal
+        seed: String,
+    },
+
+    /// Derive addresses or keys from mnemonic
+    Derive(DeriveArgs),
+
+    /// Restore mnemonic interactively word by word and show its fingerprint and first address
+    Restore {
+        /// Number of words of the mnemonic
+        #[arg(long, default_value_t = 24, value_parser = PossibleValuesParser::new(["12", "15", "18", "21", "24"]).map(|words| words.parse::<usize>().unwrap()))]
+        words: usize,
+    },
+
+    /// Render mnemonic as SeedQR code for import by camera
+    Qr {
+        /// Mnemonic sentence
+        mnemonic: String,
+
+        /// SeedQR format, standard holds word indices, compact holds entropy
+        #[arg(long, default_value = "standard", value_parser = PossibleValuesParser::new(["standard", "compact"]).map(|format| format.parse::<SeedQrFormat>().unwrap()))]
+        qr_format: SeedQrFormat,
+
+        /// Image format, png has to be written to file or piped
+        #[arg(long, value_enum, default_value_t = QrImage::Terminal)]
+        image: QrImage,
+    },
+
+    /// Decode mnemonic from payload of scanned SeedQR code
+    FromQr {
+        /// Digits of standard SeedQR or hexadecimal bytes of CompactSeedQR
+        payload: String,
+    },
+
+    /// Create printable backup sheet with numbered words, their indices in decimal and binary and fingerprint
+    Backup {
+        /// Mnemonic sentence
+        mnemonic: String,
+
+        /// Document format, pdf has to be written to file or piped
+        #[arg(long, value_enum, default_value_t = SheetFormat::Pdf)]
+        sheet: SheetFormat,
+    },
+
+    /// Apply operation to many records read from file or stdin, in parallel
+    Batch(BatchArgs),
+
+    /// Print shell completion script
+    Completions {
+        /// Target shell
+        shell: Shell,
+    },
+
+    /// Print man page
+    Man,
+}
+
+impl Command {
+    /// Whether command computes seed, so its fingerprint can be checked
+    pub fn computes_seed(&self) -> bool {
+        matches!(self, Command::ToSeed { .. } | Command::Verify { .. } | Command::Derive(_) | Command::Restore { .. } | Command::Backup { .. })
+    }
+
+    /// Whether command converts one mnemonic, its fingerprint is computed only if the passphrase is not prompted for
+    /// or the fingerprint is expected
+    pub fn converts_mnemonic(&self) -> bool {
+        matches!(self, Command::Generate { .. } | Command::ToMnemonic { .. } | Command::ToEntropy { .. } | Command::Qr { .. } | Command::FromQr { .. })
+    }
+}
+
+#[derive(Args)]
+pub struct DeriveArgs {
+    /// Mnemonic sentence
+    pub mnemonic: String,
+
+    /// Print first <COUNT> addresses, at most 1000
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u32).range(1..=1000), conflicts_with = "curve", required_unless_present = "curve")]
+    pub addresses: Option<u32>,
+
+    /// Print SLIP-0010 key of given curve
+    #[arg(long, value_parser = ["secp256k1", "nist256p1", "ed25519"])]
+    pub curve: Option<String>,
+
+    /// Derivation path [default: m/84'/0'/0'/0 for addresses, m for keys]
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Address type [default: by path purpose, p2wpkh otherwise]
+    #[arg(long, value_parser = ["p2pkh", "p2sh-p2wpkh", "p2wpkh", "p2tr"], conflicts_with = "curve")]
+    pub address_type: Option<String>,
+
+    /// Network of addresses
+    #[arg(long, value_parser = ["mainnet", "testnet", "regtest"], conflicts_with = "curve")]
+    pub network: Option<String>,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Operation applied to every record
+    #[arg(value_enum)]
+    pub operation: Operation,
+
+    /// File with records, `-` for stdin
+    #[arg(default_value = "-")]
+    pub input: String,
+
+    /// Format of records
+    #[arg(long, value_enum, default_value_t = InputFormat::Lines)]
+    pub input_format: InputFormat,
+
+    /// Interpret entropy as binary string
+    #[arg(long)]
+    pub binary: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        let cli = Cli::try_parse_from(["mnemonic", "--to-file", "out.txt", "generate", "--words", "12"]).unwrap();
+        assert_eq!(cli.io.to_file.as_deref(), Some("out.txt"));
+        assert!(matches!(cli.command, Command::Generate { words: 12 }));
+        assert_eq!(cli.io.format, Format::Text);
+        let cli = Cli::try_parse_from(["mnemonic", "to-entropy", "abandon", "--format", "json"]).unwrap();
+        assert_eq!(cli.io.format, Format::Json);
+        assert!(Cli::try_parse_from(["mnemonic", "generate", "--words", "13"]).is_err());
+        assert!(Cli::try_parse_from(["mnemonic", "derive", "abandon", "--addresses", "1", "--curve", "ed25519"]).is_err());
+        assert!(Cli::try_parse_from(["mnemonic", "derive", "abandon", "--addresses", "0"]).is_err());
+        assert!(Cli::try_parse_from(["mnemonic", "derive", "abandon", "--addresses", "1001"]).is_err());
+        assert!(Cli::try_parse_from(["mnemonic", "derive", "abandon", "--curve", "ed25519", "--from-file"]).is_ok());
+        let cli = Cli::try_parse_from(["mnemonic", "to-seed", "abandon", "--passphrase-env", "PASS"]).unwrap();
+        assert_eq!(cli.passphrase.source(), Ok(Source::Env(String::from("PASS"))));
+        let cli = Cli::try_parse_from(["mnemonic", "--passphrase-fd", "3", "to-seed", "abandon", "--no-passphrase"]).unwrap();
+        assert!(cli.passphrase.source().is_err());
+        let cli = Cli::try_parse_from(["mnemonic", "batch", "verify", "--input-format", "csv"]).unwrap();
+        assert!(matches!(cli.command, Command::Batch(BatchArgs { operation: Operation::Verify, input_format: InputFormat::Csv, .. })));
+    }
+}
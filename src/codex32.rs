@@ -0,0 +1,512 @@
+use crate::{entropy_to_mnemonic, mnemonic_to_entropy};
+
+/// Human readable part of codex32 strings
+pub const HRP: &str = "ms";
+/// Bech32 character set, position of character is its value
+pub const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const SHORT_CHECKSUM_LEN: usize = 13;
+const LONG_CHECKSUM_LEN: usize = 15;
+/// Threshold, identifier and share index
+const HEADER_LEN: usize = 6;
+/// Payload of 16 bytes, the shortest allowed seed
+const MIN_PAYLOAD_LEN: usize = 26;
+const SHORT_CONST: u128 = 0x10ce0795c2fd1e62a;
+const LONG_CONST: u128 = 0x43381e570bf4798ab26;
+const SHORT_GEN: [u128; 5] = [0x19dc500ce73fde210, 0x1bfae00def77fe529, 0x1fbd920fffe7bee52, 0x1739640bdeee3fdad, 0x07729a039cfc75f5a];
+const LONG_GEN: [u128; 5] = [0x3d59d273535ea62d897, 0x7a9becb6361c6c51507, 0x543f9b7e6c38d8a2a0e, 0x0c577eaeccf1990d13c, 0x1887f74f8dc71b10651];
+
+/// Share indices in the order in which they are assigned to generated shares
+const SHARE_INDICES: &str = "acdefghjklmnpqrtuvwxyz023456789";
+
+/// Parsed codex32 string (without checksum)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Codex32 {
+    threshold: u8,
+    identifier: String,
+    share_index: char,
+    payload: Vec<u8>,
+}
+
+/// Get value of bech32 character
+fn char_value(character: char) -> Result<u8, String> {
+    match CHARSET.find(character.to_ascii_lowercase()) {
+        None => Err(format!("Invalid codex32 character: {}", character)),
+        Some(v) => Ok(v as u8),
+    }
+}
+
+/// Get bech32 character of value
+fn value_char(value: u8) -> char {
+    CHARSET.as_bytes()[value as usize] as char
+}
+
+/// Multiply two elements of GF(32) defined by x^5 + x^3 + 1
+fn gf32_mul(lhs: u8, rhs: u8) -> u8 {
+    let mut result = 0u8;
+    let mut lhs = lhs;
+    for bit in 0..5 {
+        if (rhs >> bit) & 1 != 0 {
+            result ^= lhs;
+        }
+        lhs <<= 1;
+        if lhs & 32 != 0 {
+            lhs ^= 41;
+        }
+    }
+    result
+}
+
+/// Invert nonzero element of GF(32)
+fn gf32_inv(value: u8) -> u8 {
+    // value^30 = value^-1, since the multiplicative group has order 31
+    let mut result = 1u8;
+    for _ in 0..30 {
+        result = gf32_mul(result, value);
+    }
+    result
+}
+
+/// Compute BCH residue of values
+fn polymod(values: &[u8], long: bool) -> u128 {
+    let (generator, shift, mask) = if long {
+        (&LONG_GEN, 70, 0x3f_ffff_ffff_ffff_ffffu128)
+    } else {
+        (&SHORT_GEN, 60, 0x0fff_ffff_ffff_ffffu128)
+    };
+    let mut residue = 0x23181b3u128;
+    for &value in values {
+        let top = residue >> shift;
+        residue = ((residue & mask) << 5) ^ u128::from(value);
+        for (idx, gen) in generator.iter().enumerate() {
+            if (top >> idx) & 1 != 0 {
+                residue ^= gen;
+            }
+        }
+    }
+    residue
+}
+
+/// Compute checksum of data values, long checksum is used for data longer than 80 characters
+fn create_checksum(data: &[u8]) -> Vec<u8> {
+    let long = data.len() > 80;
+    let (len, constant) = if long { (LONG_CHECKSUM_LEN, LONG_CONST) } else { (SHORT_CHECKSUM_LEN, SHORT_CONST) };
+    let mut values = data.to_vec();
+    values.resize(data.len() + len, 0);
+    let residue = polymod(&values, long) ^ constant;
+    (0..len).map(|i| ((residue >> (5 * (len - 1 - i))) & 31) as u8).collect()
+}
+
+/// Verify checksum of data values including checksum
+fn verify_checksum(data: &[u8]) -> bool {
+    match data.len() {
+        0..=93 => polymod(data, false) == SHORT_CONST,
+        96..=124 => polymod(data, true) == LONG_CONST,
+        _ => false,
+    }
+}
+
+/// Convert bytes to 5-bit values, last value is padded with zero bits
+pub(crate) fn bytes_to_values(bytes: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        accumulator = (accumulator << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(((accumulator >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        result.push(((accumulator << (5 - bits)) & 31) as u8);
+    }
+    result
+}
+
+/// Convert 5-bit values to bytes, incomplete last byte is dropped
+fn values_to_bytes(values: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    for &value in values {
+        accumulator = (accumulator << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((accumulator >> bits) as u8);
+        }
+    }
+    result
+}
+
+impl Codex32 {
+    /// Create codex32 string encoding a master seed
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - 4 bech32 characters identifying the seed and its shares
+    /// * `threshold` - number of shares required to recover the seed (0 for unshared seed, or 2-9)
+    /// * `share_index` - index of this share, `s` for the seed itself
+    /// * `seed` - master seed, 16 to 64 bytes
+    pub fn from_seed(identifier: &str, threshold: u8, share_index: char, seed: &[u8]) -> Result<Codex32, String> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(String::from("Codex32 seed has to be between 16 and 64 bytes long!"));
+        }
+        if identifier.chars().count() != 4 {
+            return Err(String::from("Codex32 identifier has to be 4 characters long!"));
+        }
+        for character in identifier.chars() {
+            char_value(character)?;
+        }
+        char_value(share_index)?;
+        if threshold == 1 || threshold > 9 {
+            return Err(String::from("Codex32 threshold has to be 0 or between 2 and 9!"));
+        }
+        let share_index = share_index.to_ascii_lowercase();
+        if threshold == 0 && share_index != 's' {
+            return Err(String::from("Codex32 string with threshold 0 has to have share index s!"));
+        }
+        Ok(Codex32 {
+            threshold,
+            identifier: identifier.to_ascii_lowercase(),
+            share_index,
+            payload: bytes_to_values(seed),
+        })
+    }
+
+    /// Create unshared codex32 string from BIP-39 mnemonic
+    pub fn from_mnemonic(identifier: &str, mnemonic: &str) -> Result<Codex32, String> {
+        Codex32::from_seed(identifier, 0, 's', &mnemonic_to_entropy(mnemonic)?)
+    }
+
+    /// Parse and verify codex32 string
+    pub fn parse(text: &str) -> Result<Codex32, String> {
+        if text.chars().any(|c| c.is_ascii_lowercase()) && text.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(String::from("Codex32 string has to be either lowercase or uppercase!"));
+        }
+        let text = text.to_ascii_lowercase();
+        let data = match text.rfind('1') {
+            Some(pos) if &text[..pos] == HRP => &text[pos + 1..],
+            _ => return Err(String::from("Codex32 string has to start with ms1!")),
+        };
+        let values = data.chars().map(char_value).collect::<Result<Vec<_>, _>>()?;
+        if values.len() < HEADER_LEN + MIN_PAYLOAD_LEN + SHORT_CHECKSUM_LEN {
+            return Err(String::from("Invalid codex32 payload length!"));
+        }
+        if !verify_checksum(&values) {
+            return Err(String::from("Invalid codex32 checksum!"));
+        }
+
+        let checksum_len = if values.len() > 93 { LONG_CHECKSUM_LEN } else { SHORT_CHECKSUM_LEN };
+        let payload_len = values.len() - checksum_len - HEADER_LEN;
+        if payload_len < MIN_PAYLOAD_LEN || payload_len * 5 % 8 > 4 {
+            return Err(String::from("Invalid codex32 payload length!"));
+        }
+
+        let threshold = match data.as_bytes()[0] {
+            b'0' => 0,
+            digit @ b'2'..=b'9' => digit - b'0',
+            _ => return Err(String::from("Invalid codex32 threshold!")),
+        };
+        let share_index = data.as_bytes()[5] as char;
+        if threshold == 0 && share_index != 's' {
+            return Err(String::from("Codex32 string with threshold 0 has to have share index s!"));
+        }
+        Ok(Codex32 {
+            threshold,
+            identifier: data[1..5].to_string(),
+            share_index,
+            payload: values[HEADER_LEN..HEADER_LEN + payload_len].to_vec(),
+        })
+    }
+
+    /// Number of shares required to recover the seed, 0 for unshared seed
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Identifier of the seed
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Index of the share, `s` for the seed itself
+    pub fn share_index(&self) -> char {
+        self.share_index
+    }
+
+    /// Payload decoded as bytes, i.e. the master seed if this is the `s` share
+    pub fn payload(&self) -> Vec<u8> {
+        values_to_bytes(&self.payload)
+    }
+
+    /// Convert seed to BIP-39 mnemonic if it is a valid BIP-39 entropy length
+    pub fn to_mnemonic(&self) -> Result<String, String> {
+        if self.share_index != 's' {
+            return Err(String::from("Only the secret share s can be converted to mnemonic!"));
+        }
+        entropy_to_mnemonic(&self.payload()).map_err(String::from)
+    }
+
+    /// Data part values without checksum
+    fn data_values(&self) -> Vec<u8> {
+        let header = format!("{}{}{}", value_char_threshold(self.threshold), self.identifier, self.share_index);
+        let mut values: Vec<u8> = header.chars().map(|c| char_value(c).unwrap()).collect();
+        values.extend_from_slice(&self.payload);
+        values
+    }
+
+    /// Data part values including checksum
+    fn checksummed_values(&self) -> Vec<u8> {
+        let mut values = self.data_values();
+        let checksum = create_checksum(&values);
+        values.extend(checksum);
+        values
+    }
+
+    /// Build share from data part values including checksum
+    fn from_values(values: &[u8]) -> Result<Codex32, String> {
+        let text: String = values.iter().map(|&v| value_char(v)).collect();
+        Codex32::parse(&format!("{}1{}", HRP, text))
+    }
+}
+
+/// Get threshold character
+fn value_char_threshold(threshold: u8) -> char {
+    (b'0' + threshold) as char
+}
+
+impl std::fmt::Display for Codex32 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let data: String = self.checksummed_values().into_iter().map(value_char).collect();
+        write!(f, "{}1{}", HRP, data)
+    }
+}
+
+/// Derive share with index `target` from at least `threshold` shares using Lagrange interpolation
+pub fn interpolate(shares: &[Codex32], target: char) -> Result<Codex32, String> {
+    let first = match shares.first() {
+        None => return Err(String::from("No codex32 shares provided!")),
+        Some(first) => first,
+    };
+    if first.threshold == 0 {
+        return Err(String::from("Codex32 string with threshold 0 cannot be interpolated!"));
+    }
+    if shares.len() < first.threshold as usize {
+        return Err(format!("At least {} codex32 shares are required!", first.threshold));
+    }
+    let shares = &shares[..first.threshold as usize];
+    for share in shares {
+        if share.threshold != first.threshold || share.identifier != first.identifier || share.payload.len() != first.payload.len() {
+            return Err(String::from("Codex32 shares do not belong to the same seed!"));
+        }
+    }
+    for (idx, share) in shares.iter().enumerate() {
+        if shares[..idx].iter().any(|other| other.share_index == share.share_index) {
+            return Err(format!("Duplicate codex32 share index: {}", share.share_index));
+        }
+    }
+    let target = char_value(target)?;
+    if let Some(share) = shares.iter().find(|share| char_value(share.share_index).unwrap() == target) {
+        return Ok(share.clone());
+    }
+
+    let indices: Vec<u8> = shares.iter().map(|share| char_value(share.share_index).unwrap()).collect();
+    let values: Vec<Vec<u8>> = shares.iter().map(|share| share.checksummed_values()).collect();
+
+    // Lagrange basis polynomials evaluated at target
+    let mut weights = Vec::new();
+    for &i in &indices {
+        let mut numerator = 1u8;
+        let mut denominator = 1u8;
+        for &j in &indices {
+            if i != j {
+                numerator = gf32_mul(numerator, j ^ target);
+                denominator = gf32_mul(denominator, i ^ j);
+            }
+        }
+        weights.push(gf32_mul(numerator, gf32_inv(denominator)));
+    }
+
+    let result: Vec<u8> = (0..values[0].len())
+        .map(|pos| {
+            values.iter().zip(weights.iter()).fold(0u8, |acc, (share, &weight)| acc ^ gf32_mul(weight, share[pos]))
+        })
+        .collect();
+    Codex32::from_values(&result)
+}
+
+/// Recover the secret share `s` from shares
+pub fn recover(shares: &[Codex32]) -> Result<Codex32, String> {
+    interpolate(shares, 's')
+}
+
+/// Split unshared secret into `count` shares out of which `threshold` are needed for recovery
+///
+/// # Arguments
+///
+/// * `secret` - secret share `s`, its threshold is ignored
+/// * `threshold` - number of shares required for recovery (2-9)
+/// * `count` - number of generated shares (at least `threshold`, at most 31)
+/// * `randomness` - `threshold - 1` times seed length of uniformly random bytes
+pub fn split(secret: &Codex32, threshold: u8, count: usize, randomness: &[u8]) -> Result<Vec<Codex32>, String> {
+    if secret.share_index != 's' {
+        return Err(String::from("Only the secret share s can be split!"));
+    }
+    if !(2..=9).contains(&threshold) {
+        return Err(String::from("Codex32 threshold has to be between 2 and 9!"));
+    }
+    if count < threshold as usize || count > SHARE_INDICES.len() {
+        return Err(String::from("Invalid number of codex32 shares!"));
+    }
+    let seed = secret.payload();
+    if randomness.len() != (threshold as usize - 1) * seed.len() {
+        return Err(format!("Exactly {} random bytes are required!", (threshold as usize - 1) * seed.len()));
+    }
+
+    let mut base = vec![Codex32::from_seed(&secret.identifier, threshold, 's', &seed)?];
+    for (random, index) in randomness.chunks(seed.len()).zip(SHARE_INDICES.chars()) {
+        base.push(Codex32::from_seed(&secret.identifier, threshold, index, random)?);
+    }
+
+    let mut shares: Vec<Codex32> = base[1..].to_vec();
+    for index in SHARE_INDICES.chars().skip(threshold as usize - 1).take(count + 1 - threshold as usize) {
+        shares.push(interpolate(&base, index)?);
+    }
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::decode_hex;
+
+    #[test]
+    fn gf32_inverse() {
+        for value in 1..32 {
+            assert_eq!(gf32_mul(value, gf32_inv(value)), 1);
+        }
+    }
+
+    #[test]
+    fn bip93_tv1() {
+        let text = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        let secret = Codex32::parse(text).unwrap();
+        assert_eq!(secret.threshold(), 0);
+        assert_eq!(secret.identifier(), "test");
+        assert_eq!(secret.payload(), decode_hex("318c6318c6318c6318c6318c6318c631").unwrap());
+        assert_eq!(secret.to_string(), text);
+    }
+
+    #[test]
+    fn bip93_tv2() {
+        let share_a = Codex32::parse("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM").unwrap();
+        let share_c = Codex32::parse("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN").unwrap();
+        let shares = [share_a, share_c];
+        assert_eq!(interpolate(&shares, 'd').unwrap().to_string(), "ms12namedll4f8jlh4e5vdvuldlfxu2jhdnlsm97xvenrxeg");
+        let secret = recover(&shares).unwrap();
+        assert_eq!(secret.to_string(), "ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgsphuh6evw");
+        assert_eq!(secret.payload(), decode_hex("d1808e096b35b209ca12132b264662a5").unwrap());
+    }
+
+    #[test]
+    fn bip93_tv3() {
+        let shares = [
+            Codex32::parse("ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln").unwrap(),
+            Codex32::parse("ms13casha320zyxwvutsrqpnmlkjhgfedca2a8d0zehn8a0t").unwrap(),
+            Codex32::parse("ms13cashcacdefghjklmnpqrstuvwxyz023949xq35my48dr").unwrap(),
+        ];
+        assert_eq!(shares[0].payload(), decode_hex("ffeeddccbbaa99887766554433221100").unwrap());
+        assert_eq!(interpolate(&shares, 'd').unwrap().to_string(), "ms13cashd0wsedstcdcts64cd7wvy4m90lm28w4ffupqs7rm");
+        assert_eq!(interpolate(&shares, 'e').unwrap().to_string(), "ms13casheekgpemxzshcrmqhaydlp6yhms3ws7320xyxsar9");
+        assert_eq!(interpolate(&shares, 'f').unwrap().to_string(), "ms13cashf8jh6sdrkpyrsp5ut94pj8ktehhw2hfvyrj48704");
+        // threshold is 3
+        assert!(recover(&shares[1..]).is_err());
+    }
+
+    #[test]
+    fn bip93_tv4() {
+        let text = "ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma";
+        let seed = decode_hex("ffeeddccbbaa99887766554433221100ffeeddccbbaa99887766554433221100").unwrap();
+        assert_eq!(Codex32::parse(text).unwrap().payload(), seed);
+        assert_eq!(Codex32::from_seed("leet", 0, 's', &seed).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn bip93_tv5() {
+        let text = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK";
+        let secret = Codex32::parse(text).unwrap();
+        assert_eq!(secret.identifier(), "0c8v");
+        assert_eq!(secret.payload(), decode_hex("dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9").unwrap());
+        assert_eq!(secret.to_string(), text.to_lowercase());
+    }
+
+    #[test]
+    fn invalid_strings() {
+        // codex32 string of data with valid checksum
+        let with_checksum = |data: &str| {
+            let values: Vec<u8> = data.chars().map(|character| char_value(character).unwrap()).collect();
+            let checksum: String = create_checksum(&values).iter().map(|value| value_char(*value)).collect();
+            format!("{}1{}{}", HRP, data, checksum)
+        };
+        let error = |text: &str| Codex32::parse(text).unwrap_err();
+        assert_eq!(error("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlq"), "Invalid codex32 checksum!");
+        assert_eq!(error("ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nlm"), "Invalid codex32 checksum!");
+        assert_eq!(error("mt10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw"), "Codex32 string has to start with ms1!");
+        assert_eq!(error("10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw"), "Codex32 string has to start with ms1!");
+        assert_eq!(error("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmcZlw"), "Codex32 string has to be either lowercase or uppercase!");
+        assert_eq!(error("Ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw"), "Codex32 string has to be either lowercase or uppercase!");
+        assert!(error("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlb").starts_with("Invalid"));
+        let payload = "x".repeat(MIN_PAYLOAD_LEN);
+        assert!(Codex32::parse(&with_checksum(&format!("0tests{}", payload))).is_ok());
+        // threshold 1 is no bech32 character, the string is split there
+        assert_eq!(error(&format!("ms11tests{}", &with_checksum(&payload)[3..])), "Codex32 string has to start with ms1!");
+        assert_eq!(error(&with_checksum(&format!("stests{}", payload))), "Invalid codex32 threshold!");
+        assert_eq!(error(&with_checksum(&format!("0testa{}", payload))), "Codex32 string with threshold 0 has to have share index s!");
+        // payload of 16 bytes and one more character does not fit whole bytes
+        assert_eq!(error(&with_checksum(&format!("0tests{}x", payload))), "Invalid codex32 payload length!");
+    }
+
+    #[test]
+    fn too_short() {
+        // valid checksum of empty data and of header without payload
+        for data in [Vec::new(), vec![0, 1, 2, 3, 4, 16]] {
+            let checksum = create_checksum(&data);
+            let text: String = HRP.chars().chain(Some('1')).chain(data.iter().chain(&checksum).map(|value| value_char(*value))).collect();
+            assert!(verify_checksum(&data.iter().chain(&checksum).copied().collect::<Vec<_>>()));
+            assert_eq!(Codex32::parse(&text).unwrap_err(), "Invalid codex32 payload length!");
+        }
+    }
+
+    #[test]
+    fn split_and_recover() {
+        let secret = Codex32::from_seed("cash", 0, 's', &[0xab; 32]).unwrap();
+        let randomness: Vec<u8> = (0..64).collect();
+        let shares = split(&secret, 3, 5, &randomness).unwrap();
+        assert_eq!(shares.len(), 5);
+        for share in &shares {
+            assert_eq!(Codex32::parse(&share.to_string()).unwrap(), *share);
+        }
+        let subset = [shares[4].clone(), shares[1].clone(), shares[3].clone()];
+        assert_eq!(recover(&subset).unwrap().payload(), vec![0xab; 32]);
+        assert!(recover(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn long_string() {
+        let secret = Codex32::from_seed("lung", 0, 's', &[0x5a; 64]).unwrap();
+        let text = secret.to_string();
+        assert_eq!(text.len(), 3 + 6 + 103 + LONG_CHECKSUM_LEN);
+        assert_eq!(Codex32::parse(&text).unwrap(), secret);
+    }
+
+    #[test]
+    fn mnemonic_conversion() {
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let secret = Codex32::from_mnemonic("leet", mnemonic).unwrap();
+        assert_eq!(Codex32::parse(&secret.to_string()).unwrap().to_mnemonic().unwrap(), mnemonic);
+        assert!(Codex32::from_seed("leet", 0, 's', &[0u8; 17]).unwrap().to_mnemonic().is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Password protected container for mnemonics and seeds written to files
+//!
+//! Layout: magic (4) | version (1) | KDF (1) | scrypt log N (1) | scrypt r (4) | scrypt p (4) | salt (16) | nonce (12) | ciphertext and tag.
+//! The key is derived by scrypt and the content is sealed by ChaCha20-Poly1305 with the whole header as associated data.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// Magic bytes identifying the container
+pub const MAGIC: &[u8; 4] = b"MNEC";
+/// Version of the container format
+pub const VERSION: u8 = 1;
+/// Identifier of scrypt key derivation
+pub const KDF_SCRYPT: u8 = 1;
+
+pub const SALT_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12;
+pub const HEADER_SIZE: usize = MAGIC.len() + 1 + 1 + 1 + 4 + 4 + SALT_SIZE + NONCE_SIZE;
+
+const KEY_SIZE: usize = 32;
+const TAG_SIZE: usize = 16;
+/// Upper bounds of scrypt cost accepted when reading, a small margin above `KdfParams::default()` used for writing
+///
+/// The cost is read from the header before the password is checked, 2^20 * 128 B * r = 1 GiB of memory with r = 8.
+const MAX_LOG_N: u8 = 20;
+const MAX_R: u32 = 8;
+const MAX_P: u32 = 4;
+
+/// Cost parameters of scrypt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> KdfParams {
+        KdfParams { log_n: 15, r: 8, p: 1 }
+    }
+}
+
+/// Checks whether data starts with the container magic
+pub fn is_container(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn derive_key(password: &str, salt: &[u8], params: KdfParams) -> Result<[u8; KEY_SIZE], String> {
+    let scrypt_params = scrypt::Params::new(params.log_n, params.r, params.p, KEY_SIZE)
+        .map_err(|_| String::from("Invalid scrypt parameters!"))?;
+    let mut key = [0u8; KEY_SIZE];
+    scrypt::scrypt(password.as_bytes(), salt, &scrypt_params, &mut key).map_err(|_| String::from("Invalid scrypt parameters!"))?;
+    Ok(key)
+}
+
+/// Encrypt content into container
+///
+/// # Arguments
+///
+/// * `content` - content to protect
+/// * `password` - password the key is derived from
+/// * `params` - cost parameters of scrypt
+/// * `salt` - random salt of scrypt
+/// * `nonce` - random nonce of ChaCha20-Poly1305
+pub fn encrypt(content: &[u8], password: &str, params: KdfParams, salt: &[u8; SALT_SIZE], nonce: &[u8; NONCE_SIZE]) -> Result<Vec<u8>, String> {
+    let mut container = Vec::with_capacity(HEADER_SIZE + content.len() + TAG_SIZE);
+    container.extend_from_slice(MAGIC);
+    container.push(VERSION);
+    container.push(KDF_SCRYPT);
+    container.push(params.log_n);
+    container.extend_from_slice(&params.r.to_be_bytes());
+    container.extend_from_slice(&params.p.to_be_bytes());
+    container.extend_from_slice(salt);
+    container.extend_from_slice(nonce);
+
+    let key = derive_key(password, salt, params)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+    let ciphertext = cipher.encrypt(Nonce::from_slice(nonce), Payload { msg: content, aad: &container })
+        .map_err(|_| String::from("Encryption failed!"))?;
+    container.extend_from_slice(&ciphertext);
+    Ok(container)
+}
+
+/// Decrypt content of container, fails on wrong password or modified container
+///
+/// # Arguments
+///
+/// * `container` - the container
+/// * `password` - password the key is derived from
+pub fn decrypt(container: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    if !is_container(container) {
+        return Err(String::from("Not an encrypted container!"));
+    }
+    if container.len() < HEADER_SIZE + TAG_SIZE {
+        return Err(String::from("Encrypted container is truncated!"));
+    }
+    let (header, ciphertext) = container.split_at(HEADER_SIZE);
+    if header[4] != VERSION {
+        return Err(format!("Unsupported encrypted container version {}!", header[4]));
+    }
+    if header[5] != KDF_SCRYPT {
+        return Err(format!("Unsupported key derivation {}!", header[5]));
+    }
+    let params = KdfParams {
+        log_n: header[6],
+        r: u32::from_be_bytes([header[7], header[8], header[9], header[10]]),
+        p: u32::from_be_bytes([header[11], header[12], header[13], header[14]]),
+    };
+    if params.log_n > MAX_LOG_N || params.r > MAX_R || params.p > MAX_P {
+        return Err(String::from("Scrypt cost of encrypted container is too high!"));
+    }
+    let salt = &header[15..15 + SALT_SIZE];
+    let nonce = &header[15 + SALT_SIZE..];
+
+    let key = derive_key(password, salt, params)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+    cipher.decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| String::from("Wrong password or corrupted encrypted container!"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: KdfParams = KdfParams { log_n: 4, r: 8, p: 1 };
+    const SALT: [u8; SALT_SIZE] = [7u8; SALT_SIZE];
+    const NONCE: [u8; NONCE_SIZE] = [9u8; NONCE_SIZE];
+
+    #[test]
+    fn round_trip() {
+        let container = encrypt(b"abandon about", "password", PARAMS, &SALT, &NONCE).unwrap();
+        assert!(is_container(&container));
+        assert_eq!(container.len(), HEADER_SIZE + 13 + TAG_SIZE);
+        assert_eq!(decrypt(&container, "password").unwrap(), b"abandon about");
+    }
+
+    #[test]
+    fn wrong_password() {
+        let container = encrypt(b"abandon about", "password", PARAMS, &SALT, &NONCE).unwrap();
+        assert_eq!(decrypt(&container, "Password").unwrap_err(), "Wrong password or corrupted encrypted container!");
+    }
+
+    #[test]
+    fn tampered_container() {
+        let container = encrypt(b"abandon about", "password", PARAMS, &SALT, &NONCE).unwrap();
+        for position in [6, 20, HEADER_SIZE, container.len() - 1] {
+            let mut tampered = container.clone();
+            tampered[position] ^= 1;
+            assert!(decrypt(&tampered, "password").is_err());
+        }
+        let mut future = container.clone();
+        future[4] = 2;
+        assert_eq!(decrypt(&future, "password").unwrap_err(), "Unsupported encrypted container version 2!");
+        assert!(decrypt(&container[..HEADER_SIZE], "password").is_err());
+        assert!(decrypt(b"abandon about", "password").is_err());
+    }
+
+    #[test]
+    fn over_cost_header() {
+        let container = encrypt(b"abandon about", "password", PARAMS, &SALT, &NONCE).unwrap();
+        // log N, last byte of r and last byte of p
+        for (position, value) in [(6, MAX_LOG_N + 1), (10, MAX_R as u8 + 1), (14, MAX_P as u8 + 1)] {
+            let mut crafted = container.clone();
+            crafted[position] = value;
+            assert_eq!(decrypt(&crafted, "password").unwrap_err(), "Scrypt cost of encrypted container is too high!");
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Compatibility layer for the original flag based interface, e.g. `--mnemonic <mnemonic>`
+
+use crate::{check_valid_check_params, check_valid_entropy, check_valid_mnemonic, load_from_file, trim_input, STDIN};
+use crate::{handle_check_result, handle_entropy_result, handle_mnemonic_result};
+use crate::passphrase::{Password, Source};
+use crate::output::{print_error, report_value, ErrorCode, Format, Output, View, EXIT_CODES};
+use mnemonic::normalize_mnemonic;
+use mnemonic::seed::SeedDerivationParams;
+use mnemonic::strength::Policy;
+
+/// Operations of the original interface, their presence selects this parser
+const OPERATIONS: [&str; 3] = ["--entropy", "--mnemonic", "--check"];
+
+/// Checks whether arguments use the original interface
+pub fn is_legacy(arguments: &[String]) -> bool {
+    arguments.iter().skip(1).any(|argument| OPERATIONS.contains(&argument.as_str()))
+}
+
+/// Prints help
+///
+/// The interface is frozen to its original flags, newer options are available only in subcommands.
+fn print_help() {
+	println!("USAGE:");
+	println!("    {} [ARGS]", std::env::args().next().unwrap_or_else(|| String::from("cargo run --")));
+	println!("ARGS:");
+	println!("  --help                                         Print help");
+	println!("  --entropy <entropy/filepath>                   Generate mnemonic and seed from given entropy");
+	println!("  --mnemonic <mnemonic/filepath>                 Generate entropy and seed from given mnemonic");
+	println!("  --check <mnemonic/filepath> <seed/filepath>    Check if given mnemonic generates given seed");
+	println!("  --to_file <file>                               Write output to file instead of stdout");
+	println!("  --from_file                                    Load values from files. Arg params will be considered as file paths");
+	println!("  --binary                                       Interpret entropy as binary string");
+	println!();
+	println!("{}", EXIT_CODES);
+	println!();
+	println!("This interface is deprecated and gets no new options, see `--help` without other arguments for subcommands.");
+}
+
+/// Checks whether operation with name <name> has been specified more than once
+///
+/// # Arguments
+///
+/// * `param` - representation of double definition. true if operation was previously(already) defined, false otherwise
+/// * `name` - name of operation [--entropy, --mnemonic, --check]
+fn check_double_definition(param: bool, name: &str) -> Result<(), i32> {
+    if param {
+        return Err(argument_error(&format!("Double {} definition, exiting...", name)));
+    }
+    Ok(())
+}
+
+/// Checks whether user gives parameter for operation
+///
+/// # Arguments
+///
+/// * `position` - position where parameters are expected
+/// * `arguments_len` - number of arguments
+/// * `name` - operation name
+fn check_provided_params(position: usize, arguments_len: usize, name: &str) -> Result<(), i32> {
+    if position >= arguments_len {
+        return Err(argument_error(&format!("{} parameter not provided, exiting...", name)));
+    }
+    Ok(())
+}
+
+/// Prints help followed by argument error
+fn argument_error(message: &str) -> i32 {
+    print_help();
+    println!();
+    print_error(Format::Text, ErrorCode::InvalidArguments, message);
+    ErrorCode::InvalidArguments.exit_code()
+}
+
+#[derive(Default)]
+struct Options {
+    from_file: bool,
+    binary: bool,
+    to_file: Option<String>,
+    entropy: Option<String>,
+    mnemonic: Option<String>,
+    check: Option<(String, String)>,
+}
+
+
+impl Options {
+
+    /// Checks whether multiple operations have been specified
+    fn check_multiple_operations(&self) -> Result<(), i32> {
+        if self.entropy.is_some() as u8 + self.mnemonic.is_some() as u8 + self.check.is_some() as u8 > 1 {
+            return Err(argument_error("Multiple operations specified, exiting..."));
+        }
+        Ok(())
+    }
+
+    /// Checks whether at least one operation have been specified
+    fn check_at_least_one_operation(&self) -> Result<(), i32> {
+        if !(self.entropy.is_some() || self.mnemonic.is_some() || self.check.is_some()) {
+            return Err(argument_error("Nothing to do, provide operation (--entropy, --mnemonic, --check), exiting..."));
+        }
+        Ok(())
+    }
+
+    /// Load values from files if requested, `-` values from stdin, values of reports written by `--to_file` are taken
+    fn load(&mut self, password: &Password) -> Result<(), (ErrorCode, String)> {
+        let from_file = self.from_file;
+        let load = |value: &mut String, key: &str| -> Result<(), (ErrorCode, String)> {
+            if from_file || value == STDIN {
+                let content = load_from_file(value, password)?;
+                *value = report_value(&content, key).unwrap_or(content);
+            }
+            Ok(())
+        };
+        if let Some(entropy) = self.entropy.as_mut() {
+            load(entropy, "entropy")?;
+        }
+        if let Some(mnemonic) = self.mnemonic.as_mut() {
+            load(mnemonic, "mnemonic")?;
+        }
+        if let Some((check_mnemonic, check_seed)) = self.check.as_mut() {
+            load(check_mnemonic, "mnemonic")?;
+            load(check_seed, "seed")?;
+        }
+        Ok(())
+    }
+
+    fn clean_input(&mut self) {
+        if let Some(entropy) = self.entropy.as_mut() {
+            trim_input(entropy);
+        }
+        if let Some(mnemonic) = self.mnemonic.as_mut() {
+            *mnemonic = normalize_mnemonic(mnemonic);
+        }
+        if let Some((check_mnemonic, check_seed)) = self.check.as_mut() {
+            *check_mnemonic = normalize_mnemonic(check_mnemonic);
+            trim_input(check_seed);
+        }
+    }
+
+    fn check_data(&self, output: &Output) -> Result<(), i32> {
+        if let Some(entropy) = self.entropy.as_ref() {
+            if !check_valid_entropy(entropy, self.binary) {
+                return Err(output.error(ErrorCode::InvalidFormat, "Entropy parameter invalid format, only hexadecimal or binary format accepted"));
+            }
+        }
+        if let Some(mnemonic) = self.mnemonic.as_ref() {
+            if !check_valid_mnemonic(mnemonic) {
+                return Err(output.error(ErrorCode::InvalidFormat, "Mnemonic parameter invalid format, only alphabetic and whitespace characters accepted"));
+            }
+        }
+        if let Some((check_mnemonic, check_seed)) = self.check.as_ref() {
+            if !check_valid_check_params(check_mnemonic, check_seed) {
+                return Err(output.error(ErrorCode::InvalidFormat, "Check parameters invalid format, exiting..."));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse arguments of the original interface and run the operation
+pub fn run(arguments: &[String]) -> Result<(), i32> {
+    let mut options = Options::default();
+    let mut skip_n: i8 = 0; // general purpose skip arg
+
+    for position in 1..arguments.len() {
+        if skip_n > 0 {
+            skip_n -= 1;
+            continue
+        }
+        match arguments[position].as_str() {
+            "--entropy" => {
+                skip_n = 1;
+                check_double_definition(options.entropy.is_some(), &arguments[position])?;
+                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
+                options.entropy = Some(arguments[position + 1].clone());
+            },
+            "--mnemonic" => {
+                skip_n = 1;
+                check_double_definition(options.mnemonic.is_some(), &arguments[position])?;
+                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
+                options.mnemonic = Some(arguments[position + 1].clone());
+            },
+            "--check" => {
+                skip_n = 2;
+                check_double_definition(options.check.is_some(), &arguments[position])?;
+                check_provided_params(position + 2, arguments.len(), &arguments[position])?;
+                options.check = Some((arguments[position + 1].clone(), arguments[position + 2].clone()));
+            },
+            "--to_file" => {
+                skip_n = 1;
+                check_double_definition(options.to_file.is_some(), &arguments[position])?;
+                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
+                options.to_file = Some(arguments[position + 1].clone())
+            },
+            "--help" => {
+                print_help();
+                return Ok(());
+            },
+            "--from_file" => {
+                check_double_definition(options.from_file, &arguments[position])?;
+                options.from_file = true;
+            },
+            "--binary" => {
+                check_double_definition(options.binary, &arguments[position])?;
+                options.binary = true;
+            },
+            // values may be secrets, e.g. unquoted mnemonic, so only their position is shown
+            argument if argument.starts_with('-') => return Err(argument_error(&format!("Unexpected argument: {}", argument))),
+            _ => return Err(argument_error(&format!("Unexpected argument at position {}, values containing spaces have to be quoted", position))),
+        }
+    }
+
+    // check other cases
+    options.check_at_least_one_operation()?;
+    options.check_multiple_operations()?;
+
+    let output = Output {
+        to_file: options.to_file.take(),
+        format: Format::Text,
+        plain: false,
+        force: false,
+        password: None,
+        view: View::default(),
+        expected_fingerprint: None,
+        allow_weak: false,
+        passphrase_policy: Policy::default(),
+        seed_params: SeedDerivationParams::default(),
+    };
+    if let Some((check_mnemonic, check_seed)) = options.check.as_ref() {
+        if check_mnemonic == STDIN && check_seed == STDIN {
+            return Err(output.error(ErrorCode::InvalidArguments, "Only one input can be read from stdin"));
+        }
+    }
+    if let Err((code, error)) = options.load(&Password::new(Source::Prompt)) {
+        return Err(output.error(code, &error));
+    }
+
+    options.clean_input();
+    options.check_data(&output)?;
+    let passphrase = Source::Prompt;
+
+    // check format of params, call results
+    let result = if let Some(entropy) = options.entropy {
+        handle_entropy_result(&output, &passphrase, &entropy, options.binary)
+    } else if let Some(mnemonic) = options.mnemonic {
+        handle_mnemonic_result(&output, &passphrase, &mnemonic)
+    } else if let Some((check_mnemonic, check_seed)) = options.check {
+        handle_check_result(&output, &passphrase, &check_mnemonic, &check_seed)
+    } else {
+        unreachable!()
+    };
+
+    if let Err(err) = result {
+        return Err(output.error(ErrorCode::Io, &format!("IO error: {}", err)));
+    }
+
+    match result.unwrap() {
+        0 => Ok(()),
+        e => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_detection() {
+        let arguments = |list: &[&str]| list.iter().map(|argument| argument.to_string()).collect::<Vec<_>>();
+        assert!(is_legacy(&arguments(&["mnemonic", "--to_file", "out", "--entropy", "00"])));
+        assert!(is_legacy(&arguments(&["mnemonic", "--check", "m", "s"])));
+        assert!(!is_legacy(&arguments(&["mnemonic", "to-mnemonic", "00"])));
+        assert!(!is_legacy(&arguments(&["mnemonic", "--help"])));
+    }
+}
@@ -1,8 +1,44 @@
+#![cfg_attr(not(any(feature = "std", feature = "wasm", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 use sha2::{Sha256, Sha512, Digest};
 
 #[cfg(test)]
 mod util;
-
+#[cfg(feature = "std")]
+mod aez;
+#[cfg(feature = "std")]
+pub mod aezeed;
+#[cfg(feature = "std")]
+pub mod codex32;
+#[cfg(feature = "std")]
+pub mod bip32;
+#[cfg(feature = "std")]
+pub mod address;
+#[cfg(feature = "std")]
+pub mod slip10;
+#[cfg(feature = "std")]
+pub mod cardano;
+#[cfg(feature = "std")]
+pub mod container;
+#[cfg(feature = "std")]
+pub mod seedqr;
+#[cfg(feature = "std")]
+pub mod backup;
+#[cfg(feature = "std")]
+pub mod quality;
+#[cfg(feature = "std")]
+pub mod strength;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod seed;
+
+// constant is part of the public API
+#[allow(clippy::large_const_arrays)]
 pub const WORD_LIST: [&str; 2048] = include!("wordlist.in");
 
 /// Get position of word in wordlist
@@ -13,34 +49,89 @@ pub fn mnemonic_lookup(mnemonic: &str) -> Result<u16, String> {
     }
 }
 
+/// Words of the list starting with prefix, e.g. for autocomplete
+pub fn words_with_prefix(prefix: &str) -> impl Iterator<Item = &'static str> + '_ {
+    WORD_LIST.iter().copied().filter(move |word| word.starts_with(prefix))
+}
+
 /// Compute sha256 of input
-fn sha256(input: &[u8]) -> Vec<u8> {
+pub(crate) fn sha256(input: &[u8]) -> Vec<u8> {
     let mut hasher = Sha256::new();
     hasher.input(input);
     hasher.result().into_iter().collect()
 }
 
+/// Maximal number of words of mnemonic
+pub const MAX_WORDS: usize = 24;
+/// Maximal length of entropy in bytes
+pub const MAX_ENTROPY: usize = 32;
+/// Possible numbers of words of mnemonic
+const POSSIBLE_LEN: [usize; 5] = [12, 15, 18, 21, 24];
+const INVALID_LEN: &str = "Mnemonic sentence could contain just 12, 15, 18, 21 or 24 words!";
+
+/// Error of mnemonic encoding, classifies failures for the CLI, C and WebAssembly interfaces
+#[derive(Debug, Clone, PartialEq)]
+pub enum MnemonicError {
+    /// Entropy does not have 16, 20, 24, 28 or 32 bytes
+    InvalidEntropy(&'static str),
+    /// Mnemonic does not have 12, 15, 18, 21 or 24 words
+    InvalidWordCount,
+    /// Mnemonic contains word which is not in word list
+    InvalidWord(String),
+    /// Checksum of mnemonic does not match
+    InvalidChecksum,
+}
+
+impl MnemonicError {
+    /// Stable name of the kind of error, e.g. for JSON or JavaScript
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MnemonicError::InvalidEntropy(_) => "invalid_entropy",
+            MnemonicError::InvalidWordCount => "invalid_word_count",
+            MnemonicError::InvalidWord(_) => "invalid_word",
+            MnemonicError::InvalidChecksum => "invalid_checksum",
+        }
+    }
+}
+
+impl core::fmt::Display for MnemonicError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            MnemonicError::InvalidEntropy(message) => formatter.write_str(message),
+            MnemonicError::InvalidWordCount => formatter.write_str(INVALID_LEN),
+            MnemonicError::InvalidWord(word) => write!(formatter, "Invalid word: {}", word),
+            MnemonicError::InvalidChecksum => formatter.write_str("Invalid mnemonic checksum!"),
+        }
+    }
+}
+
+impl From<MnemonicError> for String {
+    fn from(error: MnemonicError) -> String {
+        format!("{}", error)
+    }
+}
+
 /// Get checksum by entropy in bytes
-fn checksum(entropy: &[u8]) -> Result<u8, String> {
+fn checksum(entropy: &[u8]) -> Result<u8, &'static str> {
     let ent = entropy.len(); // number of bytes
-    if ent % 4 != 0 {
-        return Err(String::from("Entropy is not multiple of 4!"));
+    if !ent.is_multiple_of(4) {
+        return Err("Entropy is not multiple of 4!");
     }
 
     let cs = ent / 4;
-    let header = sha256(entropy)[0]; // first byte of hash
+    let header = Sha256::digest(entropy)[0]; // first byte of hash
     match cs {
         4 => Ok(header & 0b1111_0000),
         5 => Ok(header & 0b1111_1000),
         6 => Ok(header & 0b1111_1100),
         7 => Ok(header & 0b1111_1110),
         8 => Ok(header),
-        _ => Err(String::from("Size of the block is not compatible!")),
+        _ => Err("Size of the block is not compatible!"),
     }
 }
 
-// Get n-th word of entropy ...
-fn get_word(position: usize, entropy: &[u8]) -> &str {
+// Get index of n-th word of entropy ...
+fn get_index(position: usize, entropy: &[u8]) -> u16 {
     let mut index: u16 = 0b0000_0000_0000_0000; // n-th mnemonic word of sentence
     let first_bit: usize = position * 11; // first bit of mnemonic word
 
@@ -48,43 +139,69 @@ fn get_word(position: usize, entropy: &[u8]) -> &str {
     for offset in 0..11 {
         let byte = (first_bit + offset) / 8;
         let bit = (first_bit + offset) % 8;
-        let bit_value = (entropy[byte] & (128u8 >> bit) as u8) != 0u8;
+        let bit_value = (entropy[byte] & (128u8 >> bit)) != 0u8;
         if bit_value {
             index |= (1024 >> offset) as u16;
         }
     }
+    index
+}
 
-    WORD_LIST[index as usize]
+// Get n-th word of entropy ...
+#[cfg(feature = "std")]
+pub(crate) fn get_word(position: usize, entropy: &[u8]) -> &str {
+    WORD_LIST[get_index(position, entropy) as usize]
 }
 
-/// Get words from entropy
-pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, String> {
-    let mut entropy: Vec<_> = entropy.to_vec();
-    let ms = entropy.len() * 3 / 4; // length of mnemonic sentence is 0.75 multiply of initial entropy
-    let checksum = checksum(&entropy);
-    entropy.push(checksum?); // append checksum to the end of entropy
-    let mut result = String::new();
-    for index in 0..ms {
-        result.push_str(get_word(index, &entropy));
-        if index != ms - 1 {
-            result.push(' ');
-        }
+/// Word indices of mnemonic stored without allocation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WordIndices {
+    indices: [u16; MAX_WORDS],
+    len: usize,
+}
+
+impl WordIndices {
+    pub fn as_slice(&self) -> &[u16] {
+        &self.indices[..self.len]
+    }
+
+    /// Words of the indices
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.as_slice().iter().map(|index| WORD_LIST[*index as usize])
     }
-    Ok(result)
 }
 
-/// Get entropy from mnemonic
-pub fn mnemonic_to_entropy(sentence: &str) -> Result<Vec<u8>, String> {
-    let words: Vec<_> = sentence.split(' ').collect();
-    const POSSIBLE_LEN: [usize; 5] = [12, 15, 18, 21, 24];
-    if !POSSIBLE_LEN.contains(&words.len()) {
-        return Err(String::from("Mnemonic sentence could contain just 12, 15, 18, 21 or 24 words!"));
+/// Get word indices from entropy without allocation
+pub fn entropy_to_indices(entropy: &[u8]) -> Result<WordIndices, &'static str> {
+    let checksum = checksum(entropy)?;
+    let mut data = [0u8; MAX_ENTROPY + 1];
+    data[..entropy.len()].copy_from_slice(entropy);
+    data[entropy.len()] = checksum; // append checksum to the end of entropy
+    let len = entropy.len() * 3 / 4; // length of mnemonic sentence is 0.75 multiply of initial entropy
+    let mut indices = [0u16; MAX_WORDS];
+    for (position, index) in indices.iter_mut().take(len).enumerate() {
+        *index = get_index(position, &data);
+    }
+    Ok(WordIndices { indices, len })
+}
+
+/// Get entropy from word indices without allocation, returns length of entropy written to the buffer
+///
+/// # Arguments
+///
+/// * `indices` - indices of words in word list
+/// * `entropy` - buffer of the entropy
+pub fn indices_to_entropy(indices: &[u16], entropy: &mut [u8; MAX_ENTROPY]) -> Result<usize, &'static str> {
+    if !POSSIBLE_LEN.contains(&indices.len()) {
+        return Err(INVALID_LEN);
     }
 
-    let mut result = [0u8; 33];
+    let mut result = [0u8; MAX_ENTROPY + 1];
     let mut pos = 0usize; // position of actual bit in entropy
-    for word in words {
-        let index = mnemonic_lookup(word)?;
+    for index in indices {
+        if *index as usize >= WORD_LIST.len() {
+            return Err("Invalid word index!");
+        }
         for offset in 0..11 {
             let bit_value = (index & (1024 >> offset as u16)) != 0u16;
             if bit_value {
@@ -94,54 +211,106 @@ pub fn mnemonic_to_entropy(sentence: &str) -> Result<Vec<u8>, String> {
         }
     }
     let checksum_len = pos / 33;
-    let entropy = (&result[0..(pos - checksum_len)/8]).to_vec();
-    let checksum = checksum(&entropy)?;
+    let len = (pos - checksum_len) / 8;
+    let checksum = checksum(&result[..len])?;
 
     // check if checksum is equal to last byte
-    if checksum != result[(pos - checksum_len) / 8] {
-        return Err(String::from("Invalid mnemonic checksum!"));
+    if checksum != result[len] {
+        return Err("Invalid mnemonic checksum!");
     }
-    Ok(entropy)
+    entropy[..len].copy_from_slice(&result[..len]);
+    Ok(len)
+}
+
+/// Get words from entropy
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, MnemonicError> {
+    let indices = entropy_to_indices(entropy).map_err(MnemonicError::InvalidEntropy)?;
+    Ok(indices.words().collect::<Vec<_>>().join(" "))
 }
 
-/// Transform a mnemonic to a seed
+/// Normalize mnemonic typed by user or loaded from file to lowercase words separated by single spaces
+///
+/// Any run of Unicode whitespace separates words, e.g. tabs, newlines or the Japanese ideographic space.
+pub fn normalize_mnemonic(sentence: &str) -> String {
+    sentence.split_whitespace().map(|word| word.to_lowercase()).collect::<Vec<_>>().join(" ")
+}
+
+/// Get entropy from mnemonic, words may be separated by any whitespace and use any case
+pub fn mnemonic_to_entropy(sentence: &str) -> Result<Vec<u8>, MnemonicError> {
+    let sentence = normalize_mnemonic(sentence);
+    let words: Vec<_> = sentence.split(' ').collect();
+    if !POSSIBLE_LEN.contains(&words.len()) {
+        return Err(MnemonicError::InvalidWordCount);
+    }
+    let indices = words.iter()
+        .map(|word| mnemonic_lookup(word).map_err(|_| MnemonicError::InvalidWord(String::from(*word))))
+        .collect::<Result<Vec<u16>, MnemonicError>>()?;
+    let mut entropy = [0u8; MAX_ENTROPY];
+    // word count and indices are valid, only the checksum can fail
+    let len = indices_to_entropy(&indices, &mut entropy).map_err(|_| MnemonicError::InvalidChecksum)?;
+    Ok(entropy[..len].to_vec())
+}
+
+/// Transform a mnemonic to a seed, words may be separated by any whitespace and use any case
 ///
 /// # Arguments
 ///
 ///  * `mnemonic` - the mnemonic
 ///  * `passphrase` - an optional passphrase
 pub fn mnemonic_to_seed(mnemonic: &str, passphrase: Option<&str>) -> Vec<u8> {
-    let mnemonic: Vec<_> = mnemonic.bytes().collect();
-
-    let passphrase = passphrase.unwrap_or("");
-    let passphrase = format!("mnemonic{}", passphrase);
-    let passphrase: Vec<_> = passphrase.as_str().bytes().collect();
+    seed::SeedDerivationParams::default().mnemonic_to_seed(mnemonic, passphrase)
+}
 
-    pbkdf2(&mnemonic, &passphrase, 2048)
+/// BIP-32 fingerprint of master key of seed, first 4 bytes of HASH160 of its public key
+///
+/// Identifies the wallet without revealing the mnemonic, the passphrase changes it.
+#[cfg(feature = "std")]
+pub fn fingerprint(seed: &[u8]) -> Result<[u8; 4], String> {
+    let master = bip32::ExtendedPrivateKey::new_master(seed)?;
+    let hash = address::hash160(&master.public_key());
+    Ok([hash[0], hash[1], hash[2], hash[3]])
 }
 
-/// Simplified PBKDF2 using HMAC-SHA512
+/// PBKDF2 using HMAC-SHA512
 ///
-/// Supports only output size equal to the underlying hash size i.e. 64 bytes
-fn pbkdf2(password: &[u8], salt: &[u8], iter_count: usize) -> Vec<u8> {
-    const INDEX: u32 = 1;
+/// # Arguments
+///
+/// * `password` - the password
+/// * `salt` - the salt
+/// * `iter_count` - number of iterations
+/// * `output_len` - length of the derived key in bytes
+#[cfg(any(feature = "std", test))]
+pub(crate) fn pbkdf2(password: &[u8], salt: &[u8], iter_count: usize, output_len: usize) -> Vec<u8> {
+    pbkdf2_with(hmac_sha512, password, salt, iter_count, output_len)
+}
 
-    let mut tmp = Vec::new();
-    tmp.extend_from_slice(salt);
-    tmp.extend_from_slice(&INDEX.to_be_bytes()[..]);
-    tmp = hmac_sha512(&tmp, password);
-    let mut result = tmp.clone();
+/// PBKDF2 using given HMAC, which takes data and key
+pub(crate) fn pbkdf2_with(hmac: fn(&[u8], &[u8]) -> Vec<u8>, password: &[u8], salt: &[u8], iter_count: usize, output_len: usize) -> Vec<u8> {
+    let mut result = Vec::with_capacity(output_len);
+    let mut index: u32 = 1;
+
+    while result.len() < output_len {
+        let mut tmp = Vec::new();
+        tmp.extend_from_slice(salt);
+        tmp.extend_from_slice(&index.to_be_bytes()[..]);
+        tmp = hmac(&tmp, password);
+        let mut block = tmp.clone();
+
+        for _ in 1..iter_count {
+            tmp = hmac(&tmp, password);
+            block = xor_bytes(&block, &tmp);
+        }
 
-    for _ in 1..iter_count {
-        tmp = hmac_sha512(&tmp, password);
-        result = xor_bytes(&result, &tmp);
+        let remaining = output_len - result.len();
+        result.extend_from_slice(&block[..remaining.min(block.len())]);
+        index += 1;
     }
 
     result
 }
 
 /// HMAC-SHA512
-fn hmac_sha512(data: &[u8], key: &[u8]) -> Vec<u8> {
+pub(crate) fn hmac_sha512(data: &[u8], key: &[u8]) -> Vec<u8> {
     const OPAD: [u8; 128] = [0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c, 0x5c];
     const IPAD: [u8; 128] = [0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36, 0x36];
 
@@ -175,6 +344,27 @@ fn hmac_sha512(data: &[u8], key: &[u8]) -> Vec<u8> {
     hasher.result().into_iter().collect()
 }
 
+/// HMAC-SHA256
+pub(crate) fn hmac_sha256(data: &[u8], key: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut padded_key = if key.len() <= BLOCK_SIZE { key.to_vec() } else { sha256(key) };
+    padded_key.resize(BLOCK_SIZE, 0);
+
+    let key_opad: Vec<_> = padded_key.iter().map(|byte| byte ^ 0x5c).collect();
+    let key_ipad: Vec<_> = padded_key.iter().map(|byte| byte ^ 0x36).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.input(&key_ipad[..]);
+    hasher.input(data);
+    let inner_hash: Vec<_> = hasher.result().into_iter().collect();
+
+    let mut hasher = Sha256::new();
+    hasher.input(&key_opad[..]);
+    hasher.input(&inner_hash);
+    hasher.result().into_iter().collect()
+}
+
 /// XOR byte slices of the same length and return the result as Vec<u8>
 fn xor_bytes(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
     assert_eq!(lhs.len(), rhs.len());
@@ -186,6 +376,7 @@ fn xor_bytes(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
 }
 
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
     use super::*;
     use util::decode_hex;
@@ -244,12 +435,35 @@ mod tests {
         assert_eq!(result[..], TEST_RESULT[..]);
     }
 
+    #[test]
+    fn hmac_sha256_tv1() {
+        let result = hmac_sha256(b"Hi There", &[0x0b; 20]);
+        assert_eq!(result, decode_hex("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn master_fingerprint() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(fingerprint(&mnemonic_to_seed(mnemonic, None)).unwrap(), [0x73, 0xc5, 0xda, 0x0a]);
+        // BIP-32 test vector 1
+        assert_eq!(fingerprint(&decode_hex("000102030405060708090a0b0c0d0e0f").unwrap()).unwrap(), [0x34, 0x42, 0x19, 0x3e]);
+    }
+
+    #[test]
+    fn pbkdf2_output_len() {
+        let long = pbkdf2(b"password", b"salt", 2, 96);
+        assert_eq!(long.len(), 96);
+        assert_eq!(long[..64], pbkdf2(b"password", b"salt", 2, 64)[..]);
+        assert_eq!(pbkdf2(b"password", b"salt", 2, 20)[..], long[..20]);
+    }
+
     #[test]
     fn pbkdf2_tv1() {
         let test_password: Vec<u8> = "password".bytes().collect();
         let test_salt: Vec<u8> = "salt".bytes().collect();
         const TEST_RESULT: [u8; 64] = [0x86, 0x7f, 0x70, 0xcf, 0x1a, 0xde, 0x02, 0xcf, 0xf3, 0x75, 0x25, 0x99, 0xa3, 0xa5, 0x3d, 0xc4, 0xaf, 0x34, 0xc7, 0xa6, 0x69, 0x81, 0x5a, 0xe5, 0xd5, 0x13, 0x55, 0x4e, 0x1c, 0x8c, 0xf2, 0x52, 0xc0, 0x2d, 0x47, 0x0a, 0x28, 0x5a, 0x05, 0x01, 0xba, 0xd9, 0x99, 0xbf, 0xe9, 0x43, 0xc0, 0x8f, 0x05, 0x02, 0x35, 0xd7, 0xd6, 0x8b, 0x1d, 0xa5, 0x5e, 0x63, 0xf7, 0x3b, 0x60, 0xa5, 0x7f, 0xce];
-        let result = pbkdf2(&test_password, &test_salt, 1);
+        let result = pbkdf2(&test_password, &test_salt, 1, 64);
         assert_eq!(result[..], TEST_RESULT[..]);
     }
 
@@ -258,7 +472,7 @@ mod tests {
         let test_password: Vec<u8> = "password".bytes().collect();
         let test_salt: Vec<u8> = "salt".bytes().collect();
         const TEST_RESULT: [u8; 64] = [0xe1, 0xd9, 0xc1, 0x6a, 0xa6, 0x81, 0x70, 0x8a, 0x45, 0xf5, 0xc7, 0xc4, 0xe2, 0x15, 0xce, 0xb6, 0x6e, 0x01, 0x1a, 0x2e, 0x9f, 0x00, 0x40, 0x71, 0x3f, 0x18, 0xae, 0xfd, 0xb8, 0x66, 0xd5, 0x3c, 0xf7, 0x6c, 0xab, 0x28, 0x68, 0xa3, 0x9b, 0x9f, 0x78, 0x40, 0xed, 0xce, 0x4f, 0xef, 0x5a, 0x82, 0xbe, 0x67, 0x33, 0x5c, 0x77, 0xa6, 0x06, 0x8e, 0x04, 0x11, 0x27, 0x54, 0xf2, 0x7c, 0xcf, 0x4e];
-        let result = pbkdf2(&test_password, &test_salt, 2);
+        let result = pbkdf2(&test_password, &test_salt, 2, 64);
         assert_eq!(result[..], TEST_RESULT[..]);
     }
 
@@ -267,7 +481,7 @@ mod tests {
         let test_password: Vec<u8> = "password".bytes().collect();
         let test_salt: Vec<u8> = "salt".bytes().collect();
         const TEST_RESULT: [u8; 64] = [0xd1, 0x97, 0xb1, 0xb3, 0x3d, 0xb0, 0x14, 0x3e, 0x01, 0x8b, 0x12, 0xf3, 0xd1, 0xd1, 0x47, 0x9e, 0x6c, 0xde, 0xbd, 0xcc, 0x97, 0xc5, 0xc0, 0xf8, 0x7f, 0x69, 0x02, 0xe0, 0x72, 0xf4, 0x57, 0xb5, 0x14, 0x3f, 0x30, 0x60, 0x26, 0x41, 0xb3, 0xd5, 0x5c, 0xd3, 0x35, 0x98, 0x8c, 0xb3, 0x6b, 0x84, 0x37, 0x60, 0x60, 0xec, 0xd5, 0x32, 0xe0, 0x39, 0xb7, 0x42, 0xa2, 0x39, 0x43, 0x4a, 0xf2, 0xd5];
-        let result = pbkdf2(&test_password, &test_salt, 4096);
+        let result = pbkdf2(&test_password, &test_salt, 4096, 64);
         assert_eq!(result[..], TEST_RESULT[..]);
     }
 
@@ -276,7 +490,7 @@ mod tests {
         let test_password: Vec<u8> = "passwordPASSWORDpassword".bytes().collect();
         let test_salt: Vec<u8> = "saltSALTsaltSALTsaltSALTsaltSALTsalt".bytes().collect();
         const TEST_RESULT: [u8; 64] = [0x8c, 0x05, 0x11, 0xf4, 0xc6, 0xe5, 0x97, 0xc6, 0xac, 0x63, 0x15, 0xd8, 0xf0, 0x36, 0x2e, 0x22, 0x5f, 0x3c, 0x50, 0x14, 0x95, 0xba, 0x23, 0xb8, 0x68, 0xc0, 0x05, 0x17, 0x4d, 0xc4, 0xee, 0x71, 0x11, 0x5b, 0x59, 0xf9, 0xe6, 0x0c, 0xd9, 0x53, 0x2f, 0xa3, 0x3e, 0x0f, 0x75, 0xae, 0xfe, 0x30, 0x22, 0x5c, 0x58, 0x3a, 0x18, 0x6c, 0xd8, 0x2b, 0xd4, 0xda, 0xea, 0x97, 0x24, 0xa3, 0xd3, 0xb8];
-        let result = pbkdf2(&test_password, &test_salt, 4096);
+        let result = pbkdf2(&test_password, &test_salt, 4096, 64);
         assert_eq!(result[..], TEST_RESULT[..]);
     }
 
@@ -293,5 +507,44 @@ mod tests {
             assert_eq!(test_seed, mnemonic_to_seed(&test_mnemonic, Some("TREZOR")));
         }
     }
-}
 
+    #[test]
+    fn whitespace_tolerance() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let messy = " Abandon  abandon\tabandon\nabandon\r\nabandon\u{3000}abandon abandon ABANDON abandon abandon abandon about\n";
+        assert_eq!(normalize_mnemonic(messy), mnemonic);
+        assert_eq!(mnemonic_to_entropy(messy).unwrap(), vec![0u8; 16]);
+        assert_eq!(normalize_mnemonic(" \t "), "");
+        assert!(mnemonic_to_entropy("").is_err());
+    }
+
+    #[test]
+    fn fixed_capacity() {
+        let indices = entropy_to_indices(&[0u8; 16]).unwrap();
+        assert_eq!(indices.as_slice(), [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(indices.words().last(), Some("about"));
+        let indices = entropy_to_indices(&[0xff; 32]).unwrap();
+        assert_eq!(indices.as_slice().len(), 24);
+        assert_eq!(indices.words().last(), Some("vote"));
+        assert_eq!(entropy_to_indices(&[0u8; 15]), Err("Entropy is not multiple of 4!"));
+
+        let mut entropy = [0u8; MAX_ENTROPY];
+        assert_eq!(indices_to_entropy(indices.as_slice(), &mut entropy), Ok(32));
+        assert_eq!(entropy, [0xff; 32]);
+        assert_eq!(indices_to_entropy(&[0; 12], &mut entropy), Err("Invalid mnemonic checksum!"));
+        assert_eq!(indices_to_entropy(&[2048; 12], &mut entropy), Err("Invalid word index!"));
+        assert_eq!(indices_to_entropy(&[0; 11], &mut entropy), Err(INVALID_LEN));
+    }
+
+    #[test]
+    fn error_kinds() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(mnemonic_to_entropy("abandon about"), Err(MnemonicError::InvalidWordCount));
+        let error = mnemonic_to_entropy(&mnemonic.replace("about", "Xyz")).unwrap_err();
+        assert_eq!(error, MnemonicError::InvalidWord(String::from("xyz")));
+        assert_eq!(String::from(error), "Invalid word: xyz");
+        assert_eq!(mnemonic_to_entropy(&mnemonic.replace("about", "zoo")), Err(MnemonicError::InvalidChecksum));
+        let error = entropy_to_mnemonic(&[0u8; 15]).unwrap_err();
+        assert_eq!((error.as_str(), format!("{}", error)), ("invalid_entropy", String::from("Entropy is not multiple of 4!")));
+    }
+}
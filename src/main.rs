@@ -1,80 +1,74 @@
+mod batch;
+mod cli;
+mod legacy;
+mod output;
+mod passphrase;
+mod restore;
 mod util;
 
-use mnemonic::{entropy_to_mnemonic, mnemonic_to_entropy, mnemonic_to_seed};
-use std::error::Error;
-use std::fs::File;
-use std::io::prelude::*;
-use std::path::Path;
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{CommandFactory, Parser};
+use cli::{BatchArgs, Cli, Command, DeriveArgs, QrImage, SheetFormat};
+use mnemonic::{entropy_to_mnemonic, fingerprint, mnemonic_lookup, mnemonic_to_entropy, normalize_mnemonic};
+use mnemonic::address::{derive_addresses, AddressType, Network};
+use mnemonic::backup::BackupSheet;
+use mnemonic::bip32::{parse_path, HARDENED};
+use mnemonic::slip10::{Curve, ExtendedKey};
+use mnemonic::container;
+use mnemonic::quality::{analyze_entropy, analyze_mnemonic};
+use mnemonic::seedqr::{self, SeedQrFormat};
+use passphrase::{Password, Secret, Source as PassphraseSource};
+use output::{print_error, report_value, requested_format, ErrorCode, Output, Report, Verbosity, EXIT_FAILURE, EXIT_MISMATCH};
+use serde_json::{json, Value};
+use std::io::{IsTerminal, Read};
 use util::{is_hexadecimal, decode_hex, is_binary, is_alphabetic_whitespace, binary_to_hex};
 
-/// Prints help
-fn print_help() {
-	println!("USAGE:");
-	println!("    {} [ARGS]", std::env::args().next().unwrap_or_else(|| String::from("cargo run --")));
-	println!("ARGS:");
-	println!("  --help                                         Print help");
-	println!("  --entropy <entropy/filepath>                   Generate mnemonic and seed from given entropy");
-	println!("  --mnemonic <mnemonic/filepath>                 Generate entropy and seed from given mnemonic");
-	println!("  --check <mnemonic/filepath> <seed/filepath>    Check if given mnemonic generates given seed");
-	println!("  --to_file <file>                               Write output to file instead of stdout");
-	println!("  --from_file                                    Load values from files. Arg params will be considered as file paths");
-	println!("  --binary                                       Interpret entropy as binary string");
-}
-
-/// Checks whether operation with name <name> has been specified more than once
-///
-/// # Arguments
-///
-/// * `param` - representation of double definition. true if operation was previously(already) defined, false otherwise
-/// * `name` - name of operation [--entropy, --mnemonic, --check]
-fn check_double_definition(param: bool, name: &str) -> Result<(), i32> {
-    if param {
-        print_help();
-        println!();
-        eprintln!("Double {} definition, exiting...", name);
-        return Err(1);
-    }
-    Ok(())
+/// Load passphrase from given source and check it against policy, prints error and returns exit code on failure
+fn load_passphrase(output: &Output, source: &PassphraseSource) -> Result<String, i32> {
+    let passphrase = source.load().map_err(|error| output.error(ErrorCode::InvalidPassphrase, &error))?;
+    output.check_passphrase(&passphrase)?;
+    Ok(passphrase)
 }
 
-/// Checks whether user gives parameter for operation
-///
-/// # Arguments
-///
-/// * `position` - position where parameters are expected
-/// * `arguments_len` - number of arguments
-/// * `name` - operation name
-fn check_provided_params(position: usize, arguments_len: usize, name: &str) -> Result<(), i32> {
-    if position >= arguments_len {
-        print_help();
-        println!();
-        eprintln!("{} parameter not provided, exiting...", name);
-        return Err(1);
-    }
-    Ok(())
-}
+/// Name of input read from stdin instead of argument or file
+const STDIN: &str = "-";
 
-/// Load passphrase from user
-fn load_passphrase() -> Result<String, std::io::Error> {
-    println!("Please enter passphrase: ");
-    let mut passphrase = String::new();
-    std::io::stdin().read_line(&mut passphrase)?;
-    if let Some('\n') = passphrase.chars().last() { // remove trailing newline if there is one
-        passphrase.pop();
+/// Load password encrypting the output file, which has to be given
+fn load_encryption_password(output: &Output, source: &PassphraseSource) -> Result<String, i32> {
+    if output.to_file.is_none() {
+        return Err(output.error(ErrorCode::InvalidArguments, "--encrypt requires output file"));
     }
-    Ok(passphrase)
+    let password = source.load_secret(Secret::Password, true).map_err(|error| output.error(ErrorCode::InvalidPassphrase, &error))?;
+    if password.is_empty() {
+        return Err(output.error(ErrorCode::InvalidPassphrase, "Password of encrypted file cannot be empty"));
+    }
+    Ok(password)
 }
 
-/// Strip newline, if present
-fn strip_newline(input: &mut String) {
-    if let Some('\n') = input.chars().last() {
-        input.pop();
+/// Trim leading and trailing whitespace, including CRLF line endings
+fn trim_input(input: &mut String) {
+    let trimmed = input.trim();
+    if trimmed.len() != input.len() {
+        *input = String::from(trimmed);
     }
 }
 
-/// Load file contents
-fn load_from_file(filename: &str) -> Result<String, std::io::Error> {
-    Ok(std::fs::read_to_string(&filename)?)
+/// Load file contents, stdin if filename is `-`, encrypted files are decrypted
+fn load_from_file(filename: &str, password: &Password) -> Result<String, (ErrorCode, String)> {
+    let mut content = Vec::new();
+    let read = if filename == STDIN {
+        std::io::stdin().read_to_end(&mut content).map(|_| ())
+    } else {
+        std::fs::read(filename).map(|read| content = read)
+    };
+    if read.is_err() {
+        return Err((ErrorCode::Io, String::from("Input file could not be read!")));
+    }
+    if container::is_container(&content) {
+        let password = password.get().map_err(|error| (ErrorCode::InvalidPassphrase, error))?;
+        content = container::decrypt(&content, password).map_err(|error| (ErrorCode::Decryption, format!("Input error: {}", error)))?;
+    }
+    String::from_utf8(content).map_err(|_| (ErrorCode::InvalidFormat, String::from("Input file is not valid UTF-8!")))
 }
 
 /// Parse Vec<u8> to hexadecimal string
@@ -84,63 +78,207 @@ fn to_hex_string(bytes: Vec<u8>) -> String {
 
 /// Checks whether given mnemonic has valid format
 fn check_valid_mnemonic(mnemonic: &str) -> bool {
-    is_alphabetic_whitespace(&mnemonic)
+    is_alphabetic_whitespace(mnemonic)
 }
 
 /// Checks whether given entropy has valid format
 fn check_valid_entropy(entropy: &str, binary: bool) -> bool {
-    (binary && is_binary(&entropy)) || (!binary && is_hexadecimal(&entropy))
+    (binary && is_binary(entropy)) || (!binary && is_hexadecimal(entropy))
 }
 
 /// Checks whether given mnemonic and seed has valid format
 fn check_valid_check_params(mnemonic: &str, seed: &str) -> bool {
-    check_valid_mnemonic(mnemonic) && is_hexadecimal(&seed)
+    check_valid_mnemonic(mnemonic) && is_hexadecimal(seed)
+}
+
+/// Convert hexadecimal or binary entropy to mnemonic, returns hexadecimal entropy and mnemonic
+fn convert_entropy(entropy: &str, binary: bool) -> Result<(String, String), String> {
+    let mut input_entropy = String::from(entropy);
+
+    if binary {
+        if !is_binary(&input_entropy) {
+            return Err(String::from("Non-binary input!"));
+        }
+        // if input in binary, convert it to hexadecimal
+        input_entropy = binary_to_hex(&input_entropy)?;
+    }
+
+    let entropy_value = decode_hex(&input_entropy).map_err(|_| String::from("Cannot decode hex!"))?;
+    let mnemonic = entropy_to_mnemonic(&entropy_value)?;
+    Ok((input_entropy, mnemonic))
+}
+
+/// Keys or addresses requested by user
+enum DerivationRequest {
+    Addresses {
+        count: u32,
+        path: String,
+        address_type: AddressType,
+        network: Network,
+    },
+    Key {
+        curve: Curve,
+        path: String,
+    },
+}
+
+/// Build derivation request from options, None if neither addresses nor keys were requested
+///
+/// # Arguments
+///
+/// * `addresses` - number of addresses to derive
+/// * `curve` - curve of SLIP-0010 key to derive
+/// * `path` - derivation path, defaults depend on the request
+/// * `address_type` - address type name, defaults to type given by path purpose
+/// * `network` - network name, defaults to mainnet
+fn derivation_request(addresses: Option<u32>, curve: Option<&str>, path: Option<&str>, address_type: Option<&str>, network: Option<&str>) -> Result<Option<DerivationRequest>, String> {
+    if let Some(curve) = curve {
+        if addresses.is_some() {
+            return Err(String::from("--addresses cannot be combined with --curve"));
+        }
+        if address_type.is_some() || network.is_some() {
+            return Err(String::from("Address options cannot be combined with --curve"));
+        }
+        let curve = curve.parse::<Curve>()?;
+        parse_path(path.unwrap_or("m"))?;
+        let path = String::from(path.unwrap_or("m"));
+        return Ok(Some(DerivationRequest::Key { curve, path }));
+    }
+
+    let count = match addresses {
+        None => {
+            if path.is_some() || address_type.is_some() || network.is_some() {
+                return Err(String::from("Derivation options require --addresses or --curve"));
+            }
+            return Ok(None);
+        },
+        Some(count) => count,
+    };
+    let network = network.unwrap_or("mainnet").parse::<Network>()?;
+    let purpose = match path {
+        None => None,
+        Some(path) => parse_path(path)?.first().and_then(|&index| index.checked_sub(HARDENED)),
+    };
+    let address_type = match address_type {
+        None => purpose.and_then(AddressType::from_purpose).unwrap_or(AddressType::P2wpkh),
+        Some(address_type) => address_type.parse::<AddressType>()?,
+    };
+    let path = match path {
+        None => address_type.default_path(network),
+        Some(path) => String::from(path),
+    };
+    Ok(Some(DerivationRequest::Addresses { count, path, address_type, network }))
+}
+
+/// Add word indices and checksum bits of mnemonic to report, shown only in verbose output
+pub(crate) fn add_mnemonic_details(report: &mut Report, mnemonic: &str) {
+    let indices: Result<Vec<u16>, String> = mnemonic.split(' ').map(mnemonic_lookup).collect();
+    let indices = match indices {
+        Ok(indices) if indices.len().is_multiple_of(3) => indices,
+        _ => return,
+    };
+    let checksum_length = indices.len() / 3;
+    let checksum = indices[indices.len() - 1] & ((1 << checksum_length) - 1);
+    report.add_detail("Word indices", "word_indices", Value::from(indices));
+    report.add_detail("Checksum bits", "checksum_bits", Value::from(format!("{:0width$b}", checksum, width = checksum_length)));
+}
+
+/// Add parameters of seed derivation to report unless they are those of BIP-39
+fn add_seed_params(output: &Output, report: &mut Report) {
+    if !output.seed_params.is_standard() {
+        report.add("Seed derivation", "seed_derivation", &output.seed_params.to_string());
+    }
+}
+
+/// Add fingerprint of seed to report and check it against `--expect-fingerprint`
+fn add_fingerprint(output: &Output, report: &mut Report, seed: &[u8]) -> Result<(), i32> {
+    let fingerprint = match fingerprint(seed) {
+        Err(error) => return Err(output.error(ErrorCode::InvalidDerivation, &format!("Input error: {}", error))),
+        Ok(fingerprint) => to_hex_string(fingerprint.to_vec()),
+    };
+    report.add("Fingerprint", "fingerprint", &fingerprint);
+    output.check_fingerprint(&fingerprint)
+}
+
+/// Add fingerprint of valid mnemonic converted by command and its passphrase source to report and check it
+///
+/// Conversions have to work without terminal, so the passphrase is prompted for only if the fingerprint is expected.
+fn add_mnemonic_fingerprint(output: &Output, passphrase: &PassphraseSource, report: &mut Report, mnemonic: &str) -> Result<(), i32> {
+    if *passphrase == PassphraseSource::Prompt && output.expected_fingerprint.is_none() {
+        return Ok(());
+    }
+    let pass_phrase = load_passphrase(output, passphrase)?;
+    let seed = output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase));
+    add_seed_params(output, report);
+    report.add("Fingerprint passphrase", "fingerprint_passphrase", passphrase.name());
+    add_fingerprint(output, report, &seed)
+}
+
+/// Derive requested keys or addresses from seed and add them to report
+fn build_derivation(seed: &[u8], request: &DerivationRequest, report: &mut Report) -> Result<(), String> {
+    match request {
+        DerivationRequest::Addresses { count, path, address_type, network } => {
+            let mut addresses = Vec::new();
+            for (path, address) in derive_addresses(seed, path, *address_type, *network, *count)? {
+                report.add_text(&format!("Address {}", path), "address", &address);
+                report.add_plain(address.clone());
+                addresses.push(json!({ "path": path, "address": address }));
+            }
+            report.add_json("addresses", Value::from(addresses));
+        },
+        DerivationRequest::Key { curve, path } => {
+            let key = ExtendedKey::new_master(*curve, seed).derive_path(&parse_path(path)?)?;
+            let private_key = to_hex_string(key.private_key().to_vec());
+            let chain_code = to_hex_string(key.chain_code().to_vec());
+            let public_key = to_hex_string(key.public_key());
+            report.add_text("Derivation path", "path", path);
+            report.add_text("Private key", "private_key", &private_key);
+            report.add_text("Chain code", "chain_code", &chain_code);
+            report.add_text("Public key", "public_key", &public_key);
+            report.add_json("key", json!({
+                "path": path,
+                "private_key": private_key,
+                "chain_code": chain_code,
+                "public_key": public_key,
+            }));
+        },
+    }
+    Ok(())
 }
 
 /// Handle result of mnemonic operation
 ///
 /// # Arguments
 ///
-/// * `to_file` - write result to file if Some
+/// * `output` - where and how to write the result
+/// * `passphrase` - source of the passphrase
 /// * `mnemonic` - mnemonic which will be processed or path to file which content will be processed
-fn handle_mnemonic_result(to_file: &Option<String>, mnemonic: &str) -> Result<i32, std::io::Error> {
-    let initial_entropy = match mnemonic_to_entropy(&mnemonic) {
-        Err(error) => {
-            eprintln!("Input error: {}", error);
-            return Ok(1);
-        },
+fn handle_mnemonic_result(output: &Output, passphrase: &PassphraseSource, mnemonic: &str) -> Result<i32, std::io::Error> {
+    let initial_entropy = match mnemonic_to_entropy(mnemonic) {
+        Err(error) => return Ok(output.mnemonic_error(mnemonic, &error)),
         Ok(entropy) => entropy,
     };
-    let pass_phrase = load_passphrase()?;
-
-    // Build final string
-    let mut write_mnemonic = String::from("Entered mnemonic phrase: ");
-    write_mnemonic.push_str(&mnemonic);
-    let mut write_entropy = String::from("Initial entropy: ");
-    write_entropy.push_str(&to_hex_string(initial_entropy));
-    let mut write_seed = String::from("Output seed: ");
-    write_seed.push_str(&to_hex_string(mnemonic_to_seed(&mnemonic, Some(&pass_phrase))));
-    let mut write_all = String::new();
-    write_all.push_str(&write_mnemonic);
-    write_all.push('\n');
-    write_all.push_str(&write_entropy);
-    write_all.push('\n');
-    write_all.push_str(&write_seed);
-    write_all.push('\n');
-
-    if to_file.is_some() {
-        let path = Path::new(to_file.as_ref().unwrap());
-        let display = path.display();
-
-        // create file
-        let mut file = File::create(&path)?;
-
-        // write to file
-        file.write_all(write_all.as_bytes())?;
-        println!("successfully wrote to {}", display);
-    } else {
-        print!("{}", write_all);
+    if let Err(code) = output.check_quality(&analyze_mnemonic(mnemonic).unwrap_or_default(), false) {
+        return Ok(code);
     }
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let seed = output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase));
+
+    let mut report = Report::new("mnemonic");
+    report.add("Entered mnemonic phrase", "mnemonic", mnemonic);
+    report.add("Initial entropy", "entropy", &to_hex_string(initial_entropy));
+    report.add("Output seed", "seed", &to_hex_string(seed.clone()));
+    add_seed_params(output, &mut report);
+    if let Err(code) = add_fingerprint(output, &mut report, &seed) {
+        return Ok(code);
+    }
+    add_mnemonic_details(&mut report, mnemonic);
+    report.add_mnemonic_info(mnemonic);
+
+    output.write(&report)?;
 
     Ok(0)
 }
@@ -150,324 +288,481 @@ fn handle_mnemonic_result(to_file: &Option<String>, mnemonic: &str) -> Result<i3
 ///
 /// # Arguments
 ///
-/// * `to_file` - write result to file if Some
+/// * `output` - where and how to write the result
+/// * `passphrase` - source of the passphrase
 /// * `entropy` - entropy which will be processed or path to file which content will be processed
 /// * `binary` - binary input
-fn handle_entropy_result(to_file: &Option<String>, entropy: &str, binary: bool) -> Result<i32, std::io::Error> {
-    let entropy_value: Vec<u8>;
-    let mut input_entropy = String::from(entropy);
-
-    if binary {
-        if is_binary(&input_entropy) { // if input in binary, convert it to hexadecimal
-            input_entropy = match binary_to_hex(&input_entropy) {
-                Err(error) => {
-                    eprintln!("Input error: {}", error);
-                    return Ok(1);
-                },
-                Ok(entropy) => entropy,
-            }
-        } else {
-            eprintln!("Input error: Non-binary input!");
-            return Ok(1);
-        }
-    }
-
-    entropy_value = match decode_hex(&input_entropy) {
-        Err(_) => {
-            eprintln!("Input error: Cannot decode hex!");
-            return Ok(1);
-        },
-        Ok(entropy) => entropy,
+fn handle_entropy_result(output: &Output, passphrase: &PassphraseSource, entropy: &str, binary: bool) -> Result<i32, std::io::Error> {
+    let (input_entropy, mnemonic_result) = match convert_entropy(entropy, binary) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidEntropy, &format!("Input error: {}", error))),
+        Ok(converted) => converted,
     };
+    if let Err(code) = output.check_quality(&analyze_entropy(&decode_hex(&input_entropy).expect("entropy was converted")), true) {
+        return Ok(code);
+    }
 
-    let mnemonic_result = match entropy_to_mnemonic(&entropy_value) {
-        Err(error) => {
-            eprintln!("Input error: {}", error);
-            return Ok(1);
-        },
-        Ok(mnemonic) => mnemonic,
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
     };
-
-    let pass_phrase = load_passphrase()?;
-    let seed = to_hex_string(mnemonic_to_seed(&mnemonic_result, Some(&pass_phrase)));
-
-    // Build final string
-    let mut write_entropy = String::from("Entered entropy: ");
-    write_entropy.push_str(&input_entropy);
-    let mut write_mnemonic = String::from("Output mnemonic: ");
-    write_mnemonic.push_str(&mnemonic_result);
-    let mut write_seed = String::from("Output seed: ");
-    write_seed.push_str(&seed);
-    let mut write_all = String::new();
-    write_all.push_str(&write_entropy);
-    write_all.push('\n');
-    write_all.push_str(&write_mnemonic);
-    write_all.push('\n');
-    write_all.push_str(&write_seed);
-    write_all.push('\n');
-
-    if to_file.is_some() {
-        let path = Path::new(to_file.as_ref().unwrap());
-        let display = path.display();
-
-        // create file
-        let mut file = File::create(&path)?;
-
-        // write to file
-        file.write_all(write_all.as_bytes())?;
-        println!("Successfully wrote to {}.", display);
-    } else {
-        print!("{}", write_all);
+    let seed = output.seed_params.mnemonic_to_seed(&mnemonic_result, Some(&pass_phrase));
+
+    let mut report = Report::new("entropy");
+    report.add("Entered entropy", "entropy", &input_entropy);
+    report.add("Output mnemonic", "mnemonic", &mnemonic_result);
+    report.add("Output seed", "seed", &to_hex_string(seed.clone()));
+    add_seed_params(output, &mut report);
+    if let Err(code) = add_fingerprint(output, &mut report, &seed) {
+        return Ok(code);
     }
+    add_mnemonic_details(&mut report, &mnemonic_result);
+    report.add_mnemonic_info(&mnemonic_result);
+
+    output.write(&report)?;
 
     Ok(0)
 }
 
-/// Handle result of check operation
+/// Handle result of check operation, exits with `EXIT_MISMATCH` if the seed does not match
 ///
 /// # Arguments
 ///
-/// * `to_file` - write result to file if Some
+/// * `output` - where and how to write the result
+/// * `passphrase` - source of the passphrase
 /// * `mnemonic` - mnemonic which will be processed ofr path to file which content will be processed
 /// * `seed` - seed which will be processed ofr path to file which content will be processed
-fn handle_check_result(to_file: &Option<String>, mnemonic: &str, seed: &str) -> Result<i32, std::io::Error> {
-    let pass_phrase = load_passphrase()?;
-    let computed_seed = mnemonic_to_seed(&mnemonic, Some(&pass_phrase));
-    let hex_init_seed = match decode_hex(&seed) {
-        Err(_) => {
-            eprintln!("Input error: Cannot decode hex!");
-            return Ok(1);
-        },
+fn handle_check_result(output: &Output, passphrase: &PassphraseSource, mnemonic: &str, seed: &str) -> Result<i32, std::io::Error> {
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let computed_seed = output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase));
+    let hex_init_seed = match decode_hex(seed) {
+        Err(_) => return Ok(output.error(ErrorCode::InvalidSeed, "Input error: Cannot decode hex!")),
         Ok(entropy) => entropy,
     };
+    let verified = computed_seed == hex_init_seed;
+
+    let mut report = Report::new("verify");
+    report.add("Input mnemonic", "mnemonic", mnemonic);
+    report.add("Input seed", "seed", &to_hex_string(hex_init_seed));
+    report.add("Output seed", "computed_seed", &to_hex_string(computed_seed.clone()));
+    add_seed_params(output, &mut report);
+    if let Err(code) = add_fingerprint(output, &mut report, &computed_seed) {
+        return Ok(code);
+    }
+    add_mnemonic_details(&mut report, mnemonic);
+    report.add_status(String::from(if verified { "OK" } else { "NOK" }));
+    report.add_json("verified", Value::from(verified));
+    report.add_mnemonic_info(mnemonic);
 
-    // Build final string
-    let mut result:String = String::new();
-    if computed_seed == hex_init_seed {
-        result.push_str("OK\n");
-    } else {
-        result.push_str("NOK\n");
-    }
-    let mut write_mnemonic = String::from("Input mnemonic: ");
-    write_mnemonic.push_str(&mnemonic);
-    let mut write_input_seed = String::from("Input seed: ");
-    write_input_seed.push_str(&to_hex_string(hex_init_seed));
-    let mut write_seed = String::from("Output seed: ");
-    write_seed.push_str(&to_hex_string(computed_seed));
-    let mut write_all = String::new();
-    write_all.push_str(&write_mnemonic);
-    write_all.push('\n');
-    write_all.push_str(&write_input_seed);
-    write_all.push('\n');
-    write_all.push_str(&write_seed);
-    write_all.push('\n');
-    write_all.push_str(&result);
-
-    if to_file.is_some() {
-        let path = Path::new(to_file.as_ref().unwrap());
-        let display = path.display();
-
-        // create file
-        let mut file = File::create(&path)?;
-
-        // write to file
-        file.write_all(write_all.as_bytes())?;
-
-        println!("successfully wrote to {}", display);
+    output.write(&report)?;
+
+    Ok(if verified { 0 } else { EXIT_MISMATCH })
+}
+
+/// How positional arguments are loaded
+struct Input {
+    /// Arguments are paths of files to load values from
+    from_file: bool,
+    /// Password of encrypted files
+    password: Password,
+}
+
+/// Load positional argument, from file if requested or stdin if `-`, without surrounding whitespace
+///
+/// # Arguments
+///
+/// * `output` - where errors are reported
+/// * `value` - the argument
+/// * `input_options` - how the argument is loaded
+/// * `key` - field taken from loaded file if it contains a report, e.g. `mnemonic`
+fn load_input(output: &Output, value: &str, input_options: &Input, key: &str) -> Result<String, i32> {
+    let mut input = if input_options.from_file || value == STDIN {
+        let content = load_from_file(value, &input_options.password).map_err(|(code, error)| output.error(code, &error))?;
+        report_value(&content, key).unwrap_or(content)
     } else {
-        print!("{}", write_all);
+        String::from(value)
+    };
+    trim_input(&mut input);
+    Ok(input)
+}
+
+/// Load mnemonic argument, check its format, normalize its whitespace and case and warn about its quality
+fn load_mnemonic(output: &Output, value: &str, input: &Input) -> Result<String, i32> {
+    let mnemonic = load_input(output, value, input, "mnemonic")?;
+    if !check_valid_mnemonic(&mnemonic) {
+        return Err(output.error(ErrorCode::InvalidFormat, "Mnemonic parameter invalid format, only alphabetic and whitespace characters accepted"));
     }
+    let mnemonic = normalize_mnemonic(&mnemonic);
+    // invalid mnemonics are reported by the operation
+    output.check_quality(&analyze_mnemonic(&mnemonic).unwrap_or_default(), false)?;
+    Ok(mnemonic)
+}
 
+/// Handle generate subcommand
+///
+/// # Arguments
+///
+/// * `output` - where and how to write the result
+/// * `passphrase` - source of the passphrase of the fingerprint
+/// * `words` - number of words of generated mnemonic
+fn handle_generate(output: &Output, passphrase: &PassphraseSource, words: usize) -> Result<i32, std::io::Error> {
+    let mut entropy = vec![0u8; words * 4 / 3];
+    getrandom::fill(&mut entropy).map_err(|error| std::io::Error::other(error.to_string()))?;
+    let mnemonic = match entropy_to_mnemonic(&entropy) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidEntropy, &format!("Input error: {}", error))),
+        Ok(mnemonic) => mnemonic,
+    };
+    let mut report = Report::new("generate");
+    report.add("Output entropy", "entropy", &to_hex_string(entropy));
+    report.add_result("Output mnemonic", "mnemonic", &mnemonic);
+    if let Err(code) = add_mnemonic_fingerprint(output, passphrase, &mut report, &mnemonic) {
+        return Ok(code);
+    }
+    add_mnemonic_details(&mut report, &mnemonic);
+    report.add_mnemonic_info(&mnemonic);
+    output.write(&report)?;
     Ok(0)
 }
 
-struct Options {
-    from_file: bool,
-    binary: bool,
-    to_file: Option<String>,
-    entropy: Option<String>,
-    mnemonic: Option<String>,
-    check: Option<(String, String)>,
-}
-
-impl Default for Options {
-    fn default() -> Options {
-        Options {
-            from_file: false,
-            binary: false,
-            to_file: Option::default(),
-            entropy: Option::default(),
-            mnemonic: Option::default(),
-            check: Option::default(),
-        }
+/// Handle to-mnemonic subcommand
+fn handle_to_mnemonic(output: &Output, passphrase: &PassphraseSource, entropy: &str, binary: bool) -> Result<i32, std::io::Error> {
+    let (input_entropy, mnemonic) = match convert_entropy(entropy, binary) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidEntropy, &format!("Input error: {}", error))),
+        Ok(converted) => converted,
+    };
+    if let Err(code) = output.check_quality(&analyze_entropy(&decode_hex(&input_entropy).expect("entropy was converted")), true) {
+        return Ok(code);
+    }
+    let mut report = Report::new("to-mnemonic");
+    report.add("Entered entropy", "entropy", &input_entropy);
+    report.add_result("Output mnemonic", "mnemonic", &mnemonic);
+    if let Err(code) = add_mnemonic_fingerprint(output, passphrase, &mut report, &mnemonic) {
+        return Ok(code);
     }
+    add_mnemonic_details(&mut report, &mnemonic);
+    report.add_mnemonic_info(&mnemonic);
+    output.write(&report)?;
+    Ok(0)
 }
 
-impl Options {
+/// Handle to-entropy subcommand
+fn handle_to_entropy(output: &Output, passphrase: &PassphraseSource, mnemonic: &str) -> Result<i32, std::io::Error> {
+    let entropy = match mnemonic_to_entropy(mnemonic) {
+        Err(error) => return Ok(output.mnemonic_error(mnemonic, &error)),
+        Ok(entropy) => entropy,
+    };
+    let mut report = Report::new("to-entropy");
+    report.add("Entered mnemonic phrase", "mnemonic", mnemonic);
+    report.add_result("Initial entropy", "entropy", &to_hex_string(entropy));
+    if let Err(code) = add_mnemonic_fingerprint(output, passphrase, &mut report, mnemonic) {
+        return Ok(code);
+    }
+    add_mnemonic_details(&mut report, mnemonic);
+    report.add_mnemonic_info(mnemonic);
+    output.write(&report)?;
+    Ok(0)
+}
 
-    /// Checks whether multiple operations have been specified
-    fn check_multiple_operations(&self) -> Result<(), i32> {
-        if self.entropy.is_some() as u8 + self.mnemonic.is_some() as u8 + self.check.is_some() as u8 > 1 {
-            print_help();
-            println!();
-            eprintln!("Multiple operations specified, exiting...");
-            return Err(1);
-        }
-        Ok(())
+/// Handle to-seed subcommand
+fn handle_to_seed(output: &Output, passphrase: &PassphraseSource, mnemonic: &str) -> Result<i32, std::io::Error> {
+    if let Err(error) = mnemonic_to_entropy(mnemonic) {
+        return Ok(output.mnemonic_error(mnemonic, &error));
     }
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let seed = output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase));
+    let mut report = Report::new("to-seed");
+    report.add("Entered mnemonic phrase", "mnemonic", mnemonic);
+    report.add_result("Output seed", "seed", &to_hex_string(seed.clone()));
+    add_seed_params(output, &mut report);
+    if let Err(code) = add_fingerprint(output, &mut report, &seed) {
+        return Ok(code);
+    }
+    add_mnemonic_details(&mut report, mnemonic);
+    report.add_mnemonic_info(mnemonic);
+    output.write(&report)?;
+    Ok(0)
+}
 
-    /// Checks whether at least one operation have been specified
-    fn check_at_least_one_operation(&self) -> Result<(), i32> {
-        if !(self.entropy.is_some() || self.mnemonic.is_some() || self.check.is_some()) {
-            print_help();
-            println!();
-            eprintln!("Nothing to do, provide operation (--entropy, --mnemonic, --check), exiting...");
-            return Err(1);
-        }
-        Ok(())
+/// Handle derive subcommand
+fn handle_derive(output: &Output, passphrase: &PassphraseSource, mnemonic: &str, request: &DerivationRequest) -> Result<i32, std::io::Error> {
+    if let Err(error) = mnemonic_to_entropy(mnemonic) {
+        return Ok(output.mnemonic_error(mnemonic, &error));
+    }
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let seed = output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase));
+    let mut report = Report::new("derive");
+    if let Err(code) = add_fingerprint(output, &mut report, &seed) {
+        return Ok(code);
+    }
+    if let Err(error) = build_derivation(&seed, request, &mut report) {
+        return Ok(output.error(ErrorCode::InvalidDerivation, &format!("Input error: {}", error)));
     }
+    output.write(&report)?;
+    Ok(0)
+}
 
-    fn load(&mut self) -> Result<(), std::io::Error> {
-        if self.from_file {
-            if self.entropy.is_some() {
-                self.entropy = Some(load_from_file(self.entropy.as_ref().unwrap())?)
-            }
-            if self.mnemonic.is_some() {
-                self.mnemonic = Some(load_from_file(self.mnemonic.as_ref().unwrap())?)
-            }
-            if self.check.is_some() {
-                let (check_mnemonic, check_seed) = self.check.as_ref().unwrap();
-                self.check = Some((load_from_file(check_mnemonic)?, load_from_file(check_seed)?))
-            }
-        }
-        Ok(())
+/// Handle qr subcommand
+///
+/// The fingerprint is part of JSON output, the text output is the image, so the fingerprint is printed on stderr.
+fn handle_qr(output: &Output, passphrase: &PassphraseSource, mnemonic: &str, format: SeedQrFormat, image: QrImage) -> Result<i32, std::io::Error> {
+    if output.view.redact {
+        return Ok(output.error(ErrorCode::InvalidArguments, "SeedQR code cannot be redacted"));
+    }
+    if let Err(error) = mnemonic_to_entropy(mnemonic) {
+        return Ok(output.mnemonic_error(mnemonic, &error));
+    }
+    let code = match seedqr::qr_code(mnemonic, format) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidFormat, &format!("Input error: {}", error))),
+        Ok(code) => code,
+    };
+    if image == QrImage::Png && output.to_file.is_none() && !output.plain {
+        return Ok(output.error(ErrorCode::InvalidArguments, "PNG image has to be written to file with --to-file or piped"));
+    }
+    let mut report = Report::new("qr");
+    if let Err(code) = add_mnemonic_fingerprint(output, passphrase, &mut report, mnemonic) {
+        return Ok(code);
     }
 
-    fn clean_input(&mut self) {
-        if self.entropy.is_some() {
-            strip_newline(self.entropy.as_mut().unwrap());
-        }
-        if self.mnemonic.is_some() {
-            strip_newline(self.mnemonic.as_mut().unwrap());
-        }
-        if self.check.is_some() {
-            let (check_mnemonic, check_seed) = self.check.as_mut().unwrap();
-            strip_newline(check_mnemonic);
-            strip_newline(check_seed);
+    if output.format == output::Format::Json {
+        let payload = seedqr::payload(mnemonic, format).expect("mnemonic was encoded");
+        let payload = match format {
+            SeedQrFormat::Standard => String::from_utf8(payload).expect("standard payload is digits"),
+            SeedQrFormat::Compact => to_hex_string(payload),
+        };
+        report.add_json("qr_format", Value::from(if format == SeedQrFormat::Standard { "standard" } else { "compact" }));
+        report.add_json("payload", Value::from(payload));
+        report.add_json("modules", Value::from(code.width()));
+        match image {
+            QrImage::Terminal => (),
+            QrImage::Svg => report.add_json("svg", Value::from(seedqr::to_svg(&code))),
+            QrImage::Png => return Ok(output.error(ErrorCode::InvalidArguments, "PNG image cannot be written as JSON")),
         }
+        output.write(&report)?;
+        return Ok(0);
+    }
+    if output.view.verbosity != Verbosity::Quiet {
+        eprint!("{}", report.render(output::Format::Text, &output.view));
     }
+    match image {
+        QrImage::Terminal => output.write_rendered(&seedqr::to_terminal(&code))?,
+        QrImage::Svg => output.write_rendered(&seedqr::to_svg(&code))?,
+        QrImage::Png => match seedqr::to_png(&code) {
+            Err(error) => return Ok(output.error(ErrorCode::Io, &format!("PNG error: {}", error))),
+            Ok(png) => output.write_bytes(&png)?,
+        },
+    }
+    Ok(0)
+}
 
-    fn check_data(&self) -> Result<(), i32> {
-        if let Some(entropy) = self.entropy.as_ref() {
-            if !check_valid_entropy(entropy, self.binary) {
-                eprintln!("Entropy parameter invalid format, only hexadecimal or binary format accepted");
-                return Err(1);
-            }
-        }
-        if let Some(mnemonic) = self.mnemonic.as_ref() {
-            if !check_valid_mnemonic(mnemonic) {
-                eprintln!("Mnemonic parameter invalid format, only alphabetic and whitespace characters accepted");
-                return Err(1);
-            }
-        }
-        if let Some((check_mnemonic, check_seed)) = self.check.as_ref() {
-            if !check_valid_check_params(check_mnemonic, check_seed) {
-                eprintln!("Check parameters invalid format, exiting...");
-                return Err(1);
-            }
-        }
-        Ok(())
+/// Handle from-qr subcommand, standard payloads are digits and compact payloads hexadecimal
+fn handle_from_qr(output: &Output, passphrase: &PassphraseSource, payload: &str) -> Result<i32, std::io::Error> {
+    // hexadecimal compact payload of 24 bytes could consist of digits only, standard payload takes precedence
+    let bytes = if payload.bytes().all(|digit| digit.is_ascii_digit()) && payload.len().is_multiple_of(12) {
+        payload.as_bytes().to_vec()
+    } else if is_hexadecimal(payload) {
+        decode_hex(payload).expect("payload is hexadecimal")
+    } else {
+        return Ok(output.error(ErrorCode::InvalidFormat, "SeedQR payload invalid format, only digits or hexadecimal accepted"));
+    };
+    let mnemonic = match seedqr::decode_words(&bytes) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidFormat, &format!("Input error: {}", error))),
+        Ok(mnemonic) => mnemonic,
+    };
+    if let Err(error) = mnemonic_to_entropy(&mnemonic) {
+        return Ok(output.mnemonic_error(&mnemonic, &error));
+    }
+    let mut report = Report::new("from-qr");
+    report.add("Entered payload", "payload", payload);
+    report.add_result("Output mnemonic", "mnemonic", &mnemonic);
+    if let Err(code) = add_mnemonic_fingerprint(output, passphrase, &mut report, &mnemonic) {
+        return Ok(code);
     }
+    add_mnemonic_details(&mut report, &mnemonic);
+    report.add_mnemonic_info(&mnemonic);
+    output.write(&report)?;
+    Ok(0)
 }
 
-fn run() -> Result<(), i32> {
-    let arguments: Vec<String> = std::env::args().collect();
-    let mut options = Options::default();
-    let mut skip_n: i8 = 0; // general purpose skip arg
+/// Handle backup subcommand, JSON output holds content of the sheet instead of the document
+fn handle_backup(output: &Output, passphrase: &PassphraseSource, mnemonic: &str, format: SheetFormat) -> Result<i32, std::io::Error> {
+    if output.view.redact {
+        return Ok(output.error(ErrorCode::InvalidArguments, "Backup sheet cannot be redacted"));
+    }
+    if let Err(error) = mnemonic_to_entropy(mnemonic) {
+        return Ok(output.mnemonic_error(mnemonic, &error));
+    }
+    if format == SheetFormat::Pdf && output.format == output::Format::Text && output.to_file.is_none() && !output.plain {
+        return Ok(output.error(ErrorCode::InvalidArguments, "PDF document has to be written to file with --to-file or piped"));
+    }
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let sheet = match BackupSheet::new(mnemonic, &output.seed_params.mnemonic_to_seed(mnemonic, Some(&pass_phrase))) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidDerivation, &format!("Input error: {}", error))),
+        Ok(sheet) => sheet,
+    };
+    if let Err(code) = output.check_fingerprint(&sheet.fingerprint) {
+        return Ok(code);
+    }
 
-    for position in 1..arguments.len() {
-        if skip_n > 0 {
-            skip_n -= 1;
-            continue
-        }
-        match arguments[position].as_str() {
-            "--entropy" => {
-                skip_n = 1;
-                check_double_definition(options.entropy.is_some(), &arguments[position])?;
-                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
-                options.entropy = Some(arguments[position + 1].clone());
-            },
-            "--mnemonic" => {
-                skip_n = 1;
-                check_double_definition(options.mnemonic.is_some(), &arguments[position])?;
-                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
-                options.mnemonic = Some(arguments[position + 1].clone());
-            },
-            "--check" => {
-                skip_n = 2;
-                check_double_definition(options.check.is_some(), &arguments[position])?;
-                check_provided_params(position + 2, arguments.len(), &arguments[position])?;
-                options.check = Some((arguments[position + 1].clone(), arguments[position + 2].clone()));
-            },
-            "--to_file" => {
-                skip_n = 1;
-                check_double_definition(options.to_file.is_some(), &arguments[position])?;
-                check_provided_params(position + 1, arguments.len(), &arguments[position])?;
-                options.to_file = Some(arguments[position + 1].clone())
-            },
-            "--help" => {
-                print_help();
-                return Ok(());
-            },
-            "--from_file" => {
-                check_double_definition(options.from_file, &arguments[position])?;
-                options.from_file = true;
-            },
-            "--binary" => {
-                check_double_definition(options.binary, &arguments[position])?;
-                options.binary = true;
-            },
-            _ => {
-                print_help();
-                println!();
-                eprintln!("Unexpected argument: {}", arguments[position]);
-                return Err(1);
-            }
-        }
+    if output.format == output::Format::Json {
+        let mut report = Report::new("backup");
+        report.add_json("fingerprint", Value::from(sheet.fingerprint.as_str()));
+        report.add_json("checksum_bits", Value::from(sheet.checksum_bits));
+        report.add_json("words", Value::from(sheet.words.iter().map(|word| json!({
+            "number": word.number,
+            "word": word.word,
+            "prefix": word.prefix,
+            "index": word.index,
+            "binary": word.binary,
+        })).collect::<Vec<_>>()));
+        output.write(&report)?;
+        return Ok(0);
+    }
+    match format {
+        SheetFormat::Pdf => output.write_bytes(&sheet.to_pdf())?,
+        SheetFormat::Svg => output.write_rendered(&sheet.to_svg())?,
     }
+    Ok(0)
+}
+
+/// Handle batch subcommand, returns 1 if any record failed
+fn handle_batch(output: &Output, passphrase: &PassphraseSource, password: &Password, args: &BatchArgs) -> Result<i32, std::io::Error> {
+    let input = match load_from_file(&args.input, password) {
+        Err((code, error)) => return Ok(output.error(code, &error)),
+        Ok(input) => input,
+    };
+    let records = match batch::parse_records(&input, args.input_format, args.operation) {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidFormat, &error)),
+        Ok(records) => records,
+    };
+    let pass_phrase = if batch::needs_passphrase(args.operation, &records, *passphrase == PassphraseSource::Prompt) {
+        match load_passphrase(output, passphrase) {
+            Err(code) => return Ok(code),
+            Ok(pass_phrase) => Some(pass_phrase),
+        }
+    } else {
+        None
+    };
+    let failed = batch::run(output, args.operation, &records, args.binary, pass_phrase.as_deref(), passphrase.name())?;
+    Ok(if failed > 0 { EXIT_FAILURE } else { 0 })
+}
 
-    // check other cases
-    options.check_at_least_one_operation()?;
-    options.check_multiple_operations()?;
+/// Parse derive subcommand arguments
+fn derive_request(output: &Output, args: &DeriveArgs) -> Result<DerivationRequest, i32> {
+    match derivation_request(args.addresses, args.curve.as_deref(), args.path.as_deref(), args.address_type.as_deref(), args.network.as_deref()) {
+        Err(error) => Err(output.error(ErrorCode::InvalidArguments, &error)),
+        Ok(request) => Ok(request.expect("clap requires --addresses or --curve")),
+    }
+}
 
-    if options.load().is_err() {
-        eprintln!("Input file could not be read!");
-        return Err(1);
+/// Whether clap failed on superfluous positional value rather than on unknown flag
+fn is_unexpected_value(error: &clap::Error) -> bool {
+    match error.get(ContextKind::InvalidArg) {
+        Some(ContextValue::String(argument)) => error.kind() == ErrorKind::UnknownArgument && !argument.starts_with('-'),
+        _ => false,
     }
+}
 
-    options.clean_input();
-    options.check_data()?;
+fn run() -> Result<(), i32> {
+    let arguments: Vec<String> = std::env::args().collect();
+    if legacy::is_legacy(&arguments) {
+        return legacy::run(&arguments);
+    }
 
-    // check format of params, call results
-    let result = if options.entropy.is_some() {
-        handle_entropy_result(&options.to_file, &options.entropy.unwrap(), options.binary)
-    } else if options.mnemonic.is_some() {
-        handle_mnemonic_result(&options.to_file, &options.mnemonic.unwrap())
-    } else if options.check.is_some() {
-        let (check_mnemonic, check_seed) = options.check.unwrap();
-        handle_check_result(&options.to_file, &check_mnemonic, &check_seed)
-    } else {
-        unreachable!()
+    let cli = match Cli::try_parse_from(&arguments) {
+        Ok(cli) => cli,
+        Err(error) if is_unexpected_value(&error) => {
+            // the value may be a word of unquoted mnemonic, so it is not repeated
+            print_error(requested_format(&arguments), ErrorCode::InvalidArguments, "Unexpected value, values containing spaces, e.g. mnemonics, have to be quoted");
+            return Err(error.exit_code());
+        },
+        Err(error) if !error.use_stderr() || requested_format(&arguments) == output::Format::Text => error.exit(),
+        Err(error) => {
+            let rendered = error.to_string();
+            let message = rendered.lines().next().unwrap_or_default().trim_start_matches("error: ");
+            print_error(output::Format::Json, ErrorCode::InvalidArguments, message);
+            return Err(error.exit_code());
+        },
     };
-
-    if let Err(err) = result {
-        eprintln!("IO error: {}", err.description());
-        return Err(1);
+    let mut output = Output {
+        to_file: cli.io.to_file.clone(),
+        format: cli.io.format,
+        plain: !std::io::stdout().is_terminal(),
+        force: cli.io.force,
+        password: None,
+        view: cli.io.view(),
+        expected_fingerprint: cli.io.expect_fingerprint.clone(),
+        allow_weak: cli.io.allow_weak,
+        passphrase_policy: cli.passphrase.policy(),
+        seed_params: cli.seed.params(),
+    };
+    if output.expected_fingerprint.is_some() && !cli.command.computes_seed() && !cli.command.converts_mnemonic() {
+        return Err(output.error(ErrorCode::InvalidArguments, "--expect-fingerprint cannot be used by batch, completions and man"));
+    }
+    let password = cli.io.password_source().map_err(|error| output.error(ErrorCode::InvalidArguments, &error))?;
+    let passphrase = cli.passphrase.source().map_err(|error| output.error(ErrorCode::InvalidArguments, &error))?;
+    if cli.io.encrypt {
+        output.password = Some(load_encryption_password(&output, &password)?);
     }
+    let input = Input { from_file: cli.io.from_file, password: Password::new(password) };
+
+    let result = match cli.command {
+        Command::Generate { words } => handle_generate(&output, &passphrase, words),
+        Command::ToMnemonic { entropy, binary } => {
+            let entropy = load_input(&output, &entropy, &input, "entropy")?;
+            if !check_valid_entropy(&entropy, binary) {
+                return Err(output.error(ErrorCode::InvalidFormat, "Entropy parameter invalid format, only hexadecimal or binary format accepted"));
+            }
+            handle_to_mnemonic(&output, &passphrase, &entropy, binary)
+        },
+        Command::ToEntropy { mnemonic } => handle_to_entropy(&output, &passphrase, &load_mnemonic(&output, &mnemonic, &input)?),
+        Command::ToSeed { mnemonic } => handle_to_seed(&output, &passphrase, &load_mnemonic(&output, &mnemonic, &input)?),
+        Command::Verify { mnemonic, seed } => {
+            if mnemonic == STDIN && seed == STDIN {
+                return Err(output.error(ErrorCode::InvalidArguments, "Only one input can be read from stdin"));
+            }
+            let mnemonic = load_input(&output, &mnemonic, &input, "mnemonic")?;
+            let seed = load_input(&output, &seed, &input, "seed")?;
+            if !check_valid_check_params(&mnemonic, &seed) {
+                return Err(output.error(ErrorCode::InvalidFormat, "Check parameters invalid format, exiting..."));
+            }
+            handle_check_result(&output, &passphrase, &normalize_mnemonic(&mnemonic), &seed)
+        },
+        Command::Derive(args) => {
+            let request = derive_request(&output, &args)?;
+            handle_derive(&output, &passphrase, &load_mnemonic(&output, &args.mnemonic, &input)?, &request)
+        },
+        Command::Restore { words } => restore::handle_restore(&output, &passphrase, words),
+        Command::Qr { mnemonic, qr_format, image } => handle_qr(&output, &passphrase, &load_mnemonic(&output, &mnemonic, &input)?, qr_format, image),
+        Command::FromQr { payload } => {
+            let payload = load_input(&output, &payload, &input, "payload")?;
+            handle_from_qr(&output, &passphrase, &payload)
+        },
+        Command::Backup { mnemonic, sheet } => handle_backup(&output, &passphrase, &load_mnemonic(&output, &mnemonic, &input)?, sheet),
+        Command::Batch(args) => handle_batch(&output, &passphrase, &input.password, &args),
+        Command::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+            Ok(0)
+        },
+        Command::Man => clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout()).map(|_| 0),
+    };
 
-    match result.unwrap() {
-        0 => Ok(()),
-        e => Err(e),
+    match result {
+        Err(err) => Err(output.error(ErrorCode::Io, &format!("IO error: {}", err))),
+        Ok(0) => Ok(()),
+        Ok(e) => Err(e),
     }
 }
 
@@ -0,0 +1,741 @@
+use mnemonic::container::{self, KdfParams};
+use mnemonic::{normalize_mnemonic, MnemonicError};
+use mnemonic::quality::{Finding, Severity};
+use mnemonic::seed::SeedDerivationParams;
+use mnemonic::strength::{check_passphrase, Policy};
+use serde_json::{json, Map, Value};
+use std::fs::{File, OpenOptions};
+use std::io::prelude::*;
+use std::path::Path;
+
+/// Output format of operation results and errors
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// Human readable lines
+    Text,
+    /// One JSON object per operation, errors as JSON on stderr
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Format, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown output format: {}", name)),
+        }
+    }
+}
+
+/// Find requested format in raw arguments, used before the arguments are fully parsed
+pub fn requested_format(arguments: &[String]) -> Format {
+    let mut format = Format::Text;
+    for (position, argument) in arguments.iter().enumerate() {
+        let value = match argument.strip_prefix("--format=") {
+            Some(value) => Some(value),
+            None if argument == "--format" => arguments.get(position + 1).map(|value| value.as_str()),
+            None => None,
+        };
+        if let Some(Ok(requested)) = value.map(|value| value.parse::<Format>()) {
+            format = requested;
+        }
+    }
+    format
+}
+
+/// Stable error codes reported in JSON errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorCode {
+    /// Unknown, missing, duplicate or conflicting arguments
+    InvalidArguments,
+    /// Input does not have the expected format, e.g. non-hexadecimal entropy
+    InvalidFormat,
+    /// Entropy cannot be converted to mnemonic
+    InvalidEntropy,
+    /// Mnemonic does not have valid number of words
+    InvalidMnemonic,
+    /// Mnemonic contains word which is not in word list
+    InvalidWord,
+    /// Checksum of mnemonic does not match
+    InvalidChecksum,
+    /// Seed cannot be decoded
+    InvalidSeed,
+    /// Keys or addresses cannot be derived
+    InvalidDerivation,
+    /// Passphrase or password cannot be read or its confirmation does not match
+    InvalidPassphrase,
+    /// Encrypted input cannot be decrypted, wrong password or corrupted file
+    Decryption,
+    /// Fingerprint of the seed differs from `--expect-fingerprint`
+    FingerprintMismatch,
+    /// Entropy has critical quality findings and `--allow-weak` was not given
+    WeakEntropy,
+    /// File or stream cannot be read or written
+    Io,
+}
+
+impl ErrorCode {
+    /// Code as reported in JSON
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidArguments => "invalid_arguments",
+            ErrorCode::InvalidFormat => "invalid_format",
+            ErrorCode::InvalidEntropy => "invalid_entropy",
+            ErrorCode::InvalidMnemonic => "invalid_mnemonic",
+            ErrorCode::InvalidWord => "invalid_word",
+            ErrorCode::InvalidChecksum => "invalid_checksum",
+            ErrorCode::InvalidSeed => "invalid_seed",
+            ErrorCode::InvalidDerivation => "invalid_derivation",
+            ErrorCode::InvalidPassphrase => "invalid_passphrase",
+            ErrorCode::Decryption => "decryption_failed",
+            ErrorCode::FingerprintMismatch => "fingerprint_mismatch",
+            ErrorCode::WeakEntropy => "weak_entropy",
+            ErrorCode::Io => "io_error",
+        }
+    }
+
+    /// Exit code of the process failing with this error, see `EXIT_CODES`
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::InvalidArguments => EXIT_USAGE,
+            ErrorCode::InvalidFormat | ErrorCode::InvalidEntropy | ErrorCode::InvalidMnemonic | ErrorCode::InvalidSeed | ErrorCode::WeakEntropy => EXIT_INVALID_INPUT,
+            ErrorCode::InvalidWord => EXIT_INVALID_WORD,
+            ErrorCode::InvalidChecksum => EXIT_INVALID_CHECKSUM,
+            ErrorCode::Io => EXIT_IO,
+            ErrorCode::FingerprintMismatch => EXIT_MISMATCH,
+            ErrorCode::InvalidDerivation | ErrorCode::InvalidPassphrase | ErrorCode::Decryption => EXIT_FAILURE,
+        }
+    }
+
+    /// Code of error of mnemonic encoding
+    pub fn of_mnemonic_error(error: &MnemonicError) -> ErrorCode {
+        match error {
+            MnemonicError::InvalidEntropy(_) => ErrorCode::InvalidEntropy,
+            MnemonicError::InvalidWordCount => ErrorCode::InvalidMnemonic,
+            MnemonicError::InvalidWord(_) => ErrorCode::InvalidWord,
+            MnemonicError::InvalidChecksum => ErrorCode::InvalidChecksum,
+        }
+    }
+}
+
+/// Error code and message of `mnemonic_to_entropy` error, the invalid word is replaced by its position unless `show_secrets`
+pub fn mnemonic_error(mnemonic: &str, error: &MnemonicError, show_secrets: bool) -> (ErrorCode, String) {
+    let code = ErrorCode::of_mnemonic_error(error);
+    let word = match error {
+        MnemonicError::InvalidWord(word) if !show_secrets => word,
+        _ => return (code, format!("Input error: {}", error)),
+    };
+    match normalize_mnemonic(mnemonic).split(' ').position(|other| other == word) {
+        Some(position) => (code, format!("Input error: Invalid word at position {}!", position + 1)),
+        None => (code, String::from("Input error: Invalid word!")),
+    }
+}
+
+/// Other failures, e.g. passphrase cannot be read or some batch records failed
+pub const EXIT_FAILURE: i32 = 1;
+/// Unknown, missing or conflicting arguments
+pub const EXIT_USAGE: i32 = 2;
+/// Input has invalid format, length or word count, or entropy is weak
+pub const EXIT_INVALID_INPUT: i32 = 3;
+/// Mnemonic contains word which is not in word list
+pub const EXIT_INVALID_WORD: i32 = 4;
+/// Checksum of mnemonic does not match
+pub const EXIT_INVALID_CHECKSUM: i32 = 5;
+/// Mnemonic does not generate given seed or fingerprint
+pub const EXIT_MISMATCH: i32 = 6;
+/// File or stream cannot be read or written
+pub const EXIT_IO: i32 = 7;
+
+/// Description of exit codes shown in help
+pub const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  other failure, e.g. passphrase cannot be read or some batch records failed
+  2  usage error, unknown, missing or conflicting arguments
+  3  invalid input format, length or word count, or weak entropy without --allow-weak
+  4  mnemonic word is not in word list
+  5  mnemonic checksum does not match
+  6  verification mismatch, mnemonic does not generate given seed or fingerprint
+  7  IO error, file or stream cannot be read or written";
+
+/// Print error in requested format
+pub fn print_error(format: Format, code: ErrorCode, message: &str) {
+    match format {
+        Format::Text => eprintln!("{}", message),
+        Format::Json => eprintln!("{}", json!({ "error": { "code": code.as_str(), "message": message } })),
+    }
+}
+
+/// Print warning in requested format
+pub fn print_warning(format: Format, message: &str) {
+    match format {
+        Format::Text => eprintln!("Warning: {}", message),
+        Format::Json => eprintln!("{}", json!({ "warning": { "message": message } })),
+    }
+}
+
+/// Checks whether directory can be written by other users
+#[cfg(unix)]
+fn is_world_writable(directory: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(directory).map(|metadata| metadata.permissions().mode() & 0o002 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_world_writable(_: &Path) -> bool {
+    false
+}
+
+/// Create new file readable and writable only by its owner
+fn create_private(path: &Path) -> Result<File, std::io::Error> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Write content atomically to new file with owner only permissions
+///
+/// Content is written to temporary file in the same directory and synced to disk. The file is renamed over the target
+/// with `force`, otherwise it is hard linked to the target, which fails if the target was created in the meantime.
+///
+/// # Arguments
+///
+/// * `path` - path of the target file
+/// * `content` - content of the file
+/// * `force` - overwrite existing file
+pub fn write_private_file(path: &Path, content: &[u8], force: bool) -> Result<(), std::io::Error> {
+    let already_exists = || std::io::Error::new(std::io::ErrorKind::AlreadyExists, format!("{} already exists, use --force to overwrite it", path.display()));
+    if !force && path.exists() {
+        return Err(already_exists());
+    }
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{} is not a file path", path.display())))?;
+    let temporary = directory.join(format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id()));
+
+    let result = create_private(&temporary).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()?;
+        if force {
+            return std::fs::rename(&temporary, path);
+        }
+        std::fs::hard_link(&temporary, path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::AlreadyExists => already_exists(),
+            _ => error,
+        })?;
+        std::fs::remove_file(&temporary)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+        return result;
+    }
+    // persist the rename, not supported on all platforms
+    if let Ok(directory) = File::open(directory) {
+        let _ = directory.sync_all();
+    }
+    Ok(())
+}
+
+/// Encrypt output with random salt and nonce
+fn encrypt(output: &[u8], password: &str) -> Result<Vec<u8>, std::io::Error> {
+    let mut salt = [0u8; container::SALT_SIZE];
+    let mut nonce = [0u8; container::NONCE_SIZE];
+    getrandom::fill(&mut salt).and_then(|_| getrandom::fill(&mut nonce)).map_err(|error| std::io::Error::other(error.to_string()))?;
+    container::encrypt(output, password, KdfParams::default(), &salt, &nonce).map_err(std::io::Error::other)
+}
+
+/// Find field of report written to file, None if content is not a report
+///
+/// JSON reports are searched for the key, text reports for the first line with label containing the key as a word.
+///
+/// # Arguments
+///
+/// * `content` - content of the file
+/// * `key` - name of the field, e.g. `mnemonic`
+pub fn report_value(content: &str, key: &str) -> Option<String> {
+    let content = content.trim();
+    if content.starts_with('{') {
+        return serde_json::from_str::<Value>(content).ok()?.get(key)?.as_str().map(String::from);
+    }
+    content.lines()
+        .filter_map(|line| line.split_once(": "))
+        .find(|(label, _)| label.to_lowercase().split(' ').any(|word| word == key))
+        .map(|(_, value)| String::from(value.trim()))
+}
+
+/// Keys of report fields holding secrets, masked by `--redact`
+const SECRET_KEYS: [&str; 9] = ["mnemonic", "entropy", "seed", "computed_seed", "private_key", "chain_code", "word_indices", "checksum_bits", "payload"];
+
+/// How much of report is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verbosity {
+    /// Only requested values, nothing for verification
+    Quiet,
+    /// Requested values with their inputs
+    Normal,
+    /// Also intermediate values, e.g. word indices
+    Verbose,
+}
+
+/// Arrangement of mnemonic words in text output
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Layout {
+    /// Words on one line joined by the separator
+    SingleLine,
+    /// One word per line
+    MultiLine,
+    /// One word per line preceded by its number
+    Numbered,
+}
+
+impl std::str::FromStr for Layout {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Layout, String> {
+        match name {
+            "single-line" => Ok(Layout::SingleLine),
+            "multi-line" => Ok(Layout::MultiLine),
+            "numbered" => Ok(Layout::Numbered),
+            _ => Err(format!("Unknown mnemonic layout: {}", name)),
+        }
+    }
+}
+
+/// Options of report rendering
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub verbosity: Verbosity,
+    /// Mask secrets except their first and last characters
+    pub redact: bool,
+    /// Layout of mnemonics in text
+    pub layout: Layout,
+    /// Separator of words of single line mnemonics in text
+    pub separator: String,
+}
+
+impl Default for View {
+    fn default() -> View {
+        View { verbosity: Verbosity::Normal, redact: false, layout: Layout::SingleLine, separator: String::from(" ") }
+    }
+}
+
+impl View {
+    /// Arrange words of mnemonic according to layout
+    pub fn layout_mnemonic(&self, mnemonic: &str) -> String {
+        let words = mnemonic.split_whitespace();
+        match self.layout {
+            Layout::SingleLine => words.collect::<Vec<_>>().join(&self.separator),
+            Layout::MultiLine => words.collect::<Vec<_>>().join("\n"),
+            Layout::Numbered => words.enumerate().map(|(position, word)| format!("{}. {}", position + 1, word)).collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
+/// Mask all characters except the first and the last one
+pub fn redact(value: &str) -> String {
+    let count = value.chars().count();
+    value.chars().enumerate()
+        .map(|(position, character)| if count > 2 && (position == 0 || position == count - 1) { character } else { '*' })
+        .collect()
+}
+
+/// Value shown in text, arrays as space separated items
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(" "),
+        value => value.to_string(),
+    }
+}
+
+/// Mask values of secret fields, also in nested objects
+fn redact_fields(fields: &Map<String, Value>) -> Map<String, Value> {
+    fields.iter().map(|(key, value)| {
+        let value = match value {
+            Value::Object(nested) => Value::Object(redact_fields(nested)),
+            value if SECRET_KEYS.contains(&key.as_str()) => Value::from(redact(&display(value))),
+            value => value.clone(),
+        };
+        (key.clone(), value)
+    }).collect()
+}
+
+/// Part of report a text line belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// Inputs and other context, shown in text
+    Context,
+    /// Requested value, shown also in plain and quiet text
+    Result,
+    /// Requested value shown only in plain and quiet text
+    PlainResult,
+    /// Outcome of verification, shown in text and plain but not in quiet text
+    Status,
+    /// Intermediate value, shown only in verbose text
+    Detail,
+}
+
+#[derive(Debug)]
+struct Line {
+    kind: Kind,
+    label: Option<String>,
+    value: String,
+    secret: bool,
+    /// Value is mnemonic arranged by layout
+    mnemonic: bool,
+}
+
+impl Line {
+    fn render(&self, view: &View, labeled: bool) -> String {
+        let value = match (self.secret && view.redact, self.mnemonic) {
+            (true, _) => redact(&self.value),
+            (false, true) => view.layout_mnemonic(&self.value),
+            (false, false) => self.value.clone(),
+        };
+        match &self.label {
+            // multi-line values start on the line after their label
+            Some(label) if labeled && value.contains('\n') => format!("{}:\n{}\n", label, value),
+            Some(label) if labeled => format!("{}: {}\n", label, value),
+            _ => format!("{}\n", value),
+        }
+    }
+}
+
+/// Result of an operation, rendered as text lines or as one JSON object
+#[derive(Debug)]
+pub struct Report {
+    lines: Vec<Line>,
+    fields: Map<String, Value>,
+    details: Map<String, Value>,
+}
+
+impl Report {
+    /// Create report of operation with given name
+    pub fn new(operation: &str) -> Report {
+        let mut fields = Map::new();
+        fields.insert(String::from("operation"), Value::from(operation));
+        Report { lines: Vec::new(), fields, details: Map::new() }
+    }
+
+    fn push(&mut self, kind: Kind, label: Option<&str>, key: Option<&str>, value: &str) {
+        let secret = key.is_some_and(|key| SECRET_KEYS.contains(&key));
+        let mnemonic = key == Some("mnemonic");
+        self.lines.push(Line { kind, label: label.map(String::from), value: String::from(value), secret, mnemonic });
+    }
+
+    /// Add value shown as `<label>: <value>` line in text and as field `key` in JSON
+    pub fn add(&mut self, label: &str, key: &str, value: &str) {
+        self.push(Kind::Context, Some(label), Some(key), value);
+        self.fields.insert(String::from(key), Value::from(value));
+    }
+
+    /// Add value which is also the plain result of the operation, see `render_plain`
+    pub fn add_result(&mut self, label: &str, key: &str, value: &str) {
+        self.push(Kind::Result, Some(label), Some(key), value);
+        self.fields.insert(String::from(key), Value::from(value));
+    }
+
+    /// Add line shown only in plain text
+    pub fn add_plain(&mut self, line: String) {
+        self.push(Kind::PlainResult, None, None, &line);
+    }
+
+    /// Add outcome of verification shown in text and plain text, but not in quiet text
+    pub fn add_status(&mut self, line: String) {
+        self.push(Kind::Status, None, None, &line);
+    }
+
+    /// Add `<label>: <value>` line shown only in text, `key` of its JSON field decides if it is a secret
+    pub fn add_text(&mut self, label: &str, key: &str, value: &str) {
+        self.push(Kind::Context, Some(label), Some(key), value);
+    }
+
+    /// Add intermediate value shown only in verbose output
+    pub fn add_detail(&mut self, label: &str, key: &str, value: Value) {
+        self.push(Kind::Detail, Some(label), Some(key), &display(&value));
+        self.details.insert(String::from(key), value);
+    }
+
+    /// Add field shown only in JSON
+    pub fn add_json(&mut self, key: &str, value: Value) {
+        self.fields.insert(String::from(key), value);
+    }
+
+    /// Add word count and language of mnemonic to JSON
+    pub fn add_mnemonic_info(&mut self, mnemonic: &str) {
+        self.add_json("word_count", Value::from(mnemonic.split_whitespace().count()));
+        self.add_json("language", Value::from("english"));
+    }
+
+    /// Render report in given format
+    pub fn render(&self, format: Format, view: &View) -> String {
+        let verbose = view.verbosity == Verbosity::Verbose;
+        match format {
+            Format::Text => self.lines.iter()
+                .filter(|line| line.kind != Kind::PlainResult && (line.kind != Kind::Detail || verbose))
+                .map(|line| line.render(view, true))
+                .collect(),
+            Format::Json => {
+                let mut fields = self.fields.clone();
+                if verbose {
+                    fields.extend(self.details.clone());
+                }
+                if view.redact {
+                    fields = redact_fields(&fields);
+                }
+                format!("{}\n", Value::Object(fields))
+            },
+        }
+    }
+
+    /// Render only results and verification outcome without labels, so they can be piped to another command
+    ///
+    /// Reports without results, e.g. of derived keys, are rendered as text.
+    pub fn render_plain(&self, view: &View) -> String {
+        let quiet = view.verbosity == Verbosity::Quiet;
+        if !self.lines.iter().any(|line| matches!(line.kind, Kind::Result | Kind::PlainResult | Kind::Status)) {
+            return self.render(Format::Text, view);
+        }
+        self.lines.iter()
+            .filter(|line| matches!(line.kind, Kind::Result | Kind::PlainResult) || (line.kind == Kind::Status && !quiet))
+            .map(|line| line.render(view, false))
+            .collect()
+    }
+}
+
+/// Where and how operation results are written
+pub struct Output {
+    pub to_file: Option<String>,
+    pub format: Format,
+    /// Print only plain results of text reports to stdout, used when it is not a terminal
+    pub plain: bool,
+    /// Overwrite existing output file
+    pub force: bool,
+    /// Encrypt output file with password
+    pub password: Option<String>,
+    /// Verbosity and redaction of reports
+    pub view: View,
+    /// Fingerprint the seed has to have, lowercase hexadecimal
+    pub expected_fingerprint: Option<String>,
+    /// Accept entropy with critical quality findings
+    pub allow_weak: bool,
+    /// Requirements on passphrase
+    pub passphrase_policy: Policy,
+    /// Parameters of seed derivation
+    pub seed_params: SeedDerivationParams,
+}
+
+impl Output {
+    /// Write report to file if given, print it otherwise
+    pub fn write(&self, report: &Report) -> Result<(), std::io::Error> {
+        let plain = match self.view.verbosity {
+            Verbosity::Quiet => true,
+            Verbosity::Normal => self.plain && self.to_file.is_none(),
+            Verbosity::Verbose => false,
+        };
+        if plain && self.format == Format::Text {
+            self.write_rendered(&report.render_plain(&self.view))
+        } else {
+            self.write_rendered(&report.render(self.format, &self.view))
+        }
+    }
+
+    /// Write already rendered output to file if given, print it otherwise
+    pub fn write_rendered(&self, output: &str) -> Result<(), std::io::Error> {
+        self.write_bytes(output.as_bytes())
+    }
+
+    /// Write binary output, e.g. image, to file if given, print it otherwise
+    pub fn write_bytes(&self, output: &[u8]) -> Result<(), std::io::Error> {
+        if let Some(to_file) = &self.to_file {
+            let path = Path::new(to_file);
+            let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+            if is_world_writable(directory) {
+                print_warning(self.format, &format!("output directory {} is writable by other users", directory.display()));
+            }
+
+            match &self.password {
+                None => write_private_file(path, output, self.force)?,
+                Some(password) => write_private_file(path, &encrypt(output, password)?, self.force)?,
+            }
+            if self.format == Format::Text {
+                eprintln!("Successfully wrote to {}.", path.display());
+            }
+        } else {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(output)?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Print error and return exit code
+    pub fn error(&self, code: ErrorCode, message: &str) -> i32 {
+        print_error(self.format, code, message);
+        code.exit_code()
+    }
+
+    /// Check fingerprint of seed against expected one, prints error and returns exit code if they differ
+    pub fn check_fingerprint(&self, fingerprint: &str) -> Result<(), i32> {
+        match &self.expected_fingerprint {
+            Some(expected) if expected != fingerprint => {
+                Err(self.error(ErrorCode::FingerprintMismatch, &format!("Fingerprint {} does not match expected fingerprint {}", fingerprint, expected)))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Print quality findings as warnings, critical ones fail with error unless weak entropy is allowed
+    ///
+    /// # Arguments
+    ///
+    /// * `findings` - findings of `quality::analyze_entropy` or `quality::analyze_mnemonic`
+    /// * `strict` - critical findings fail, used when new mnemonic is created from the entropy
+    pub fn check_quality(&self, findings: &[Finding], strict: bool) -> Result<(), i32> {
+        for finding in findings {
+            print_warning(self.format, &format!("{} ({})", finding.message, finding.severity.as_str()));
+        }
+        if strict && !self.allow_weak && findings.iter().any(|finding| finding.severity == Severity::Critical) {
+            return Err(self.error(ErrorCode::WeakEntropy, "Input error: Entropy is weak, use --allow-weak to accept it"));
+        }
+        Ok(())
+    }
+
+    /// Print passphrase findings as warnings, policy violations fail with error
+    pub fn check_passphrase(&self, passphrase: &str) -> Result<(), i32> {
+        let findings = check_passphrase(passphrase, &self.passphrase_policy);
+        let (violations, warnings): (Vec<&Finding>, Vec<&Finding>) = findings.iter().partition(|finding| finding.severity == Severity::Critical);
+        for finding in warnings {
+            print_warning(self.format, &finding.message);
+        }
+        if !violations.is_empty() {
+            let messages: Vec<&str> = violations.iter().map(|finding| finding.message.as_str()).collect();
+            return Err(self.error(ErrorCode::InvalidPassphrase, &format!("Passphrase does not satisfy policy: {}", messages.join(", "))));
+        }
+        Ok(())
+    }
+
+    /// Print error of invalid mnemonic and return exit code, invalid word is shown only in verbose mode
+    pub fn mnemonic_error(&self, mnemonic: &str, error: &MnemonicError) -> i32 {
+        let (code, message) = mnemonic_error(mnemonic, error, self.view.verbosity == Verbosity::Verbose);
+        self.error(code, &message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_report() {
+        let mut report = Report::new("to-entropy");
+        report.add("Entered mnemonic phrase", "mnemonic", "abandon about");
+        report.add_status(String::from("OK"));
+        report.add_json("verified", Value::from(true));
+        let view = View::default();
+        assert_eq!(report.render(Format::Text, &view), "Entered mnemonic phrase: abandon about\nOK\n");
+        assert_eq!(report.render(Format::Json, &view), "{\"operation\":\"to-entropy\",\"mnemonic\":\"abandon about\",\"verified\":true}\n");
+        assert_eq!(report.render_plain(&view), "OK\n");
+        report.add_result("Initial entropy", "entropy", "00");
+        report.add_plain(String::from("NOK"));
+        assert_eq!(report.render_plain(&view), "OK\n00\nNOK\n");
+    }
+
+    #[test]
+    fn render_views() {
+        let mut report = Report::new("verify");
+        report.add("Input mnemonic", "mnemonic", "abandon about");
+        report.add_detail("Word indices", "word_indices", json!([0, 3]));
+        report.add_status(String::from("OK"));
+        let quiet = View { verbosity: Verbosity::Quiet, ..View::default() };
+        let verbose = View { verbosity: Verbosity::Verbose, ..View::default() };
+        let redacted = View { verbosity: Verbosity::Verbose, redact: true, ..View::default() };
+        assert_eq!(report.render_plain(&quiet), "");
+        assert_eq!(report.render_plain(&View::default()), "OK\n");
+        assert_eq!(report.render(Format::Text, &View::default()), "Input mnemonic: abandon about\nOK\n");
+        assert_eq!(report.render(Format::Text, &verbose), "Input mnemonic: abandon about\nWord indices: 0 3\nOK\n");
+        assert_eq!(report.render(Format::Text, &redacted), "Input mnemonic: a***********t\nWord indices: 0*3\nOK\n");
+        assert_eq!(report.render(Format::Json, &redacted), "{\"operation\":\"verify\",\"mnemonic\":\"a***********t\",\"word_indices\":\"0*3\"}\n");
+        assert_eq!(redact("ab"), "**");
+    }
+
+    #[test]
+    fn mnemonic_layouts() {
+        let mut report = Report::new("to-mnemonic");
+        report.add("Entered entropy", "entropy", "00");
+        report.add_result("Output mnemonic", "mnemonic", "abandon about");
+        let dashed = View { separator: String::from("-"), ..View::default() };
+        let numbered = View { layout: Layout::Numbered, ..View::default() };
+        let lines = View { layout: Layout::MultiLine, ..View::default() };
+        assert_eq!(report.render(Format::Text, &dashed), "Entered entropy: 00\nOutput mnemonic: abandon-about\n");
+        assert_eq!(report.render(Format::Text, &numbered), "Entered entropy: 00\nOutput mnemonic:\n1. abandon\n2. about\n");
+        assert_eq!(report.render_plain(&lines), "abandon\nabout\n");
+        assert_eq!(report.render(Format::Json, &numbered), "{\"operation\":\"to-mnemonic\",\"entropy\":\"00\",\"mnemonic\":\"abandon about\"}\n");
+        assert_eq!("multi-line".parse::<Layout>(), Ok(Layout::MultiLine));
+    }
+
+    #[test]
+    fn report_values() {
+        let mut report = Report::new("entropy");
+        report.add("Entered entropy", "entropy", "00");
+        report.add("Output mnemonic", "mnemonic", "abandon about");
+        report.add("Output seed", "seed", "5eb0");
+        for format in [Format::Text, Format::Json] {
+            let content = report.render(format, &View::default());
+            assert_eq!(report_value(&content, "mnemonic").as_deref(), Some("abandon about"));
+            assert_eq!(report_value(&content, "seed").as_deref(), Some("5eb0"));
+        }
+        assert_eq!(report_value("abandon about\n", "mnemonic"), None);
+    }
+
+    #[test]
+    fn private_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("seed.txt");
+        write_private_file(&path, b"first", false).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+        assert_eq!(write_private_file(&path, b"second", false).unwrap_err().kind(), std::io::ErrorKind::AlreadyExists);
+        write_private_file(&path, b"second", true).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn mnemonic_error_messages() {
+        let mnemonic = "abandon xyz abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let error = mnemonic::mnemonic_to_entropy(mnemonic).unwrap_err();
+        assert_eq!(mnemonic_error(mnemonic, &error, false), (ErrorCode::InvalidWord, String::from("Input error: Invalid word at position 2!")));
+        assert_eq!(mnemonic_error(mnemonic, &error, true), (ErrorCode::InvalidWord, String::from("Input error: Invalid word: xyz")));
+    }
+
+    #[test]
+    fn mnemonic_errors() {
+        let code = |mnemonic: &str| ErrorCode::of_mnemonic_error(&mnemonic::mnemonic_to_entropy(mnemonic).unwrap_err());
+        assert_eq!(code("abandon abandon"), ErrorCode::InvalidMnemonic);
+        assert_eq!(code("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon xyz"), ErrorCode::InvalidWord);
+        assert_eq!(code("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"), ErrorCode::InvalidChecksum);
+    }
+
+    #[test]
+    fn format_detection() {
+        let arguments = |list: &[&str]| list.iter().map(|argument| argument.to_string()).collect::<Vec<_>>();
+        assert_eq!(requested_format(&arguments(&["mnemonic", "--format", "json", "generate"])), Format::Json);
+        assert_eq!(requested_format(&arguments(&["mnemonic", "generate", "--format=json"])), Format::Json);
+        assert_eq!(requested_format(&arguments(&["mnemonic", "--format"])), Format::Text);
+    }
+}
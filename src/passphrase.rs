@@ -0,0 +1,210 @@
+//! Sources of the passphrase protecting the seed and of the password of encrypted files
+
+use std::cell::OnceCell;
+
+/// Kind of secret read from a source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Secret {
+    /// BIP-39 passphrase extending the mnemonic
+    Passphrase,
+    /// Password of encrypted files
+    Password,
+}
+
+impl Secret {
+    fn name(self) -> &'static str {
+        match self {
+            Secret::Passphrase => "Passphrase",
+            Secret::Password => "Password",
+        }
+    }
+
+    /// Options selecting other sources, suggested when terminal is not available
+    fn options(self) -> &'static str {
+        match self {
+            Secret::Passphrase => "--passphrase-file, --passphrase-env, --passphrase-fd or --no-passphrase",
+            Secret::Password => "--password-file or --password-env",
+        }
+    }
+}
+
+/// Where the passphrase is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// Ask twice on the controlling terminal with echo disabled
+    Prompt,
+    /// First line of file
+    File(String),
+    /// Value of environment variable
+    Env(String),
+    /// First line read from already open file descriptor
+    Fd(u32),
+    /// Empty passphrase, nothing is asked
+    Empty,
+}
+
+impl Source {
+    /// Select source from options, at most one of them may be given
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - path of file containing the passphrase
+    /// * `env` - name of environment variable containing the passphrase
+    /// * `fd` - number of file descriptor to read the passphrase from
+    /// * `none` - use empty passphrase
+    pub fn from_options(file: Option<String>, env: Option<String>, fd: Option<u32>, none: bool) -> Result<Source, String> {
+        if cfg!(not(unix)) && fd.is_some() {
+            return Err(String::from("Passphrase file descriptor is supported only on unix"));
+        }
+        let mut sources = Vec::new();
+        sources.extend(file.map(Source::File));
+        sources.extend(env.map(Source::Env));
+        sources.extend(fd.map(Source::Fd));
+        if none {
+            sources.push(Source::Empty);
+        }
+        match sources.len() {
+            0 => Ok(Source::Prompt),
+            1 => Ok(sources.remove(0)),
+            _ => Err(String::from("Only one passphrase source can be given")),
+        }
+    }
+
+    /// Name of the source, which tells what passphrase a fingerprint was computed with
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Prompt => "prompt",
+            Source::File(_) => "file",
+            Source::Env(_) => "environment variable",
+            Source::Fd(_) => "file descriptor",
+            Source::Empty => "empty",
+        }
+    }
+
+    /// Read the passphrase
+    pub fn load(&self) -> Result<String, String> {
+        self.load_secret(Secret::Passphrase, true)
+    }
+
+    /// Read secret of given kind
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - kind of the secret
+    /// * `confirm` - prompt asks for the secret twice
+    pub fn load_secret(&self, secret: Secret, confirm: bool) -> Result<String, String> {
+        let name = secret.name();
+        match self {
+            Source::Prompt => prompt(secret, confirm),
+            Source::File(path) => std::fs::read_to_string(path)
+                .map(first_line)
+                .map_err(|error| format!("{} file could not be read: {}", name, error)),
+            Source::Env(variable) => std::env::var(variable)
+                .map_err(|error| format!("{} environment variable {} could not be read: {}", name, variable, error)),
+            Source::Fd(fd) => read_fd(*fd)
+                .map_err(|error| format!("{} could not be read from file descriptor {}: {}", name, fd, error)),
+            Source::Empty => Ok(String::new()),
+        }
+    }
+}
+
+/// Password of encrypted files, loaded from its source at most once
+pub struct Password {
+    source: Source,
+    value: OnceCell<String>,
+}
+
+impl Password {
+    pub fn new(source: Source) -> Password {
+        Password { source, value: OnceCell::new() }
+    }
+
+    /// Load password without confirmation, only the first call reads the source
+    pub fn get(&self) -> Result<&str, String> {
+        if let Some(password) = self.value.get() {
+            return Ok(password);
+        }
+        let password = self.source.load_secret(Secret::Password, false)?;
+        Ok(self.value.get_or_init(|| password))
+    }
+}
+
+/// Ask for secret and optionally its confirmation on the terminal
+fn prompt(secret: Secret, confirm: bool) -> Result<String, String> {
+    let name = secret.name().to_lowercase();
+    let read = |message: &str| rpassword::prompt_password(message).map_err(|error| {
+        format!("{} could not be read from terminal ({}), use {}", secret.name(), error, secret.options())
+    });
+    let value = read(&format!("Please enter {}: ", name))?;
+    if confirm && read(&format!("Please confirm {}: ", name))? != value {
+        return Err(format!("{}s do not match", secret.name()));
+    }
+    Ok(value)
+}
+
+/// Read first line from file descriptor inherited from the caller, which stays open
+///
+/// Only the line is read, so the writer does not have to close its end.
+#[cfg(unix)]
+fn read_fd(fd: u32) -> std::io::Result<String> {
+    use std::convert::TryFrom;
+    use std::io::BufRead;
+    use std::os::unix::io::FromRawFd;
+    let fd = i32::try_from(fd).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "descriptor is out of range"))?;
+    // SAFETY: the descriptor is not closed, reading from a descriptor which is not open fails with EBADF
+    let file = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(fd) });
+    let mut line = String::new();
+    std::io::BufReader::new(&*file).read_line(&mut line)?;
+    Ok(first_line(line))
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: u32) -> std::io::Result<String> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "file descriptors are supported only on unix"))
+}
+
+/// Passphrase is the first line of file content, without LF or CRLF line ending
+fn first_line(content: String) -> String {
+    content.lines().next().map(String::from).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_selection() {
+        assert_eq!(Source::from_options(None, None, None, false), Ok(Source::Prompt));
+        assert_eq!(Source::from_options(None, Some(String::from("PASS")), None, false), Ok(Source::Env(String::from("PASS"))));
+        assert_eq!(Source::from_options(None, None, None, true), Ok(Source::Empty));
+        assert!(Source::from_options(Some(String::from("file")), None, Some(3), false).is_err());
+    }
+
+    #[test]
+    fn load_sources() {
+        std::env::set_var("MNEMONIC_TEST_PASSPHRASE", "TREZOR");
+        assert_eq!(Source::Env(String::from("MNEMONIC_TEST_PASSPHRASE")).load(), Ok(String::from("TREZOR")));
+        assert!(Source::Env(String::from("MNEMONIC_TEST_PASSPHRASE_UNSET")).load().is_err());
+        assert_eq!(Source::Empty.load(), Ok(String::new()));
+        assert!(Source::File(String::from("/nonexistent")).load_secret(Secret::Password, false).unwrap_err().starts_with("Password file"));
+        let password = Password::new(Source::Env(String::from("MNEMONIC_TEST_PASSPHRASE")));
+        assert_eq!(password.get(), Ok("TREZOR"));
+        assert_eq!(first_line(String::from("TREZOR\nsecond\n")), "TREZOR");
+        assert_eq!(first_line(String::from("TREZOR")), "TREZOR");
+        assert_eq!(first_line(String::from(" TREZOR \r\n")), " TREZOR ");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_fd() {
+        use std::io::Write;
+        use std::os::unix::io::AsRawFd;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"TREZOR\nsecond\n").unwrap();
+        let reader = std::fs::File::open(file.path()).unwrap();
+        assert_eq!(Source::Fd(reader.as_raw_fd() as u32).load(), Ok(String::from("TREZOR")));
+        // descriptor stays open
+        assert!(reader.metadata().is_ok());
+        assert!(Source::Fd(u32::MAX).load().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Quality checks of user supplied entropy and mnemonics
+//!
+//! The checks look for patterns typical for entropy typed by hand: repeated bytes and patterns, arithmetic sequences,
+//! dice rolls or binary typed as hexadecimal and published test vectors. Statistical tests follow NIST SP 800-22
+//! (frequency and runs tests), but short entropy gives them little power, so their findings are only warnings.
+//! Findings never contain the entropy itself.
+
+use crate::{mnemonic_to_entropy, normalize_mnemonic, WORD_LIST};
+
+/// Entropy of published BIP-39 test vectors, wallets created from them are swept immediately
+const KNOWN_WEAK: [&str; 17] = [
+    "00000000000000000000000000000000",
+    "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+    "80808080808080808080808080808080",
+    "ffffffffffffffffffffffffffffffff",
+    "000000000000000000000000000000000000000000000000",
+    "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+    "808080808080808080808080808080808080808080808080",
+    "ffffffffffffffffffffffffffffffffffffffffffffffff",
+    "0000000000000000000000000000000000000000000000000000000000000000",
+    "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+    "8080808080808080808080808080808080808080808080808080808080808080",
+    "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    "9e885d952ad362caeb4efe34a8e91bd2",
+    "6610b25967cdcca9d59875f5cb50b0ea75433311869e930b",
+    "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+    "c0ba5a8e914111210f2bd131f3d5e08d",
+    "f585c11aec520db57dd353c69554b21a89b20fb0650966fa0a9d6f74fd989d8f",
+];
+
+/// Longest pattern which is detected when repeated over the whole entropy, in bytes
+const MAX_PATTERN: usize = 4;
+/// Shortest run of identical bytes reported
+const REPEATED_BYTES: usize = 4;
+/// erfc(x) drops below 0.001 for x above this, the significance level of statistical tests
+const ERFC_LIMIT: f64 = 2.326;
+
+/// Severity of finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Unusual but possible for random entropy
+    Warning,
+    /// Entropy is practically guessable
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// Result of failed check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Name of the check
+    pub check: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: &'static str, message: &str) -> Finding {
+        Finding { severity, check, message: String::from(message) }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn bits(entropy: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    entropy.iter().flat_map(|byte| (0..8).rev().map(move |bit| byte >> bit & 1 == 1))
+}
+
+/// Whether entropy repeats pattern of at most `MAX_PATTERN` bytes
+fn is_periodic(entropy: &[u8]) -> bool {
+    (1..=MAX_PATTERN.min(entropy.len() / 2)).any(|period| entropy.iter().enumerate().skip(period).all(|(position, byte)| *byte == entropy[position - period]))
+}
+
+/// Whether bytes grow or fall by the same nonzero step, e.g. 00 01 02 03
+fn is_sequence(entropy: &[u8]) -> bool {
+    let step = entropy[1].wrapping_sub(entropy[0]);
+    step != 0 && entropy.windows(2).all(|pair| pair[1].wrapping_sub(pair[0]) == step)
+}
+
+/// Length of the longest run of identical bytes
+fn longest_byte_run(entropy: &[u8]) -> usize {
+    let mut longest = 1;
+    let mut current = 1;
+    for pair in entropy.windows(2) {
+        current = if pair[0] == pair[1] { current + 1 } else { 1 };
+        longest = longest.max(current);
+    }
+    longest
+}
+
+/// Argument of erfc in NIST frequency (monobit) test
+fn monobit_statistic(entropy: &[u8]) -> f64 {
+    let length = (entropy.len() * 8) as f64;
+    let sum: f64 = bits(entropy).map(|bit| if bit { 1.0 } else { -1.0 }).sum();
+    sum.abs() / length.sqrt() / std::f64::consts::SQRT_2
+}
+
+/// Argument of erfc in NIST runs test, None if the frequency prerequisite of the test fails
+fn runs_statistic(entropy: &[u8]) -> Option<f64> {
+    let length = (entropy.len() * 8) as f64;
+    let ones = bits(entropy).filter(|bit| *bit).count() as f64 / length;
+    if (ones - 0.5).abs() >= 2.0 / length.sqrt() {
+        return None;
+    }
+    let bits: Vec<bool> = bits(entropy).collect();
+    let runs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let expected = 2.0 * length * ones * (1.0 - ones);
+    Some((runs as f64 - expected).abs() / (2.0 * (2.0 * length).sqrt() * ones * (1.0 - ones)))
+}
+
+/// Analyze entropy, findings are sorted from the most severe
+pub fn analyze_entropy(entropy: &[u8]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if entropy.len() < 2 {
+        return findings;
+    }
+    if KNOWN_WEAK.contains(&to_hex(entropy).as_str()) {
+        findings.push(Finding::new(Severity::Critical, "known_weak", "Entropy is a published test vector"));
+    }
+    if is_periodic(entropy) {
+        findings.push(Finding::new(Severity::Critical, "repeated_pattern", "Entropy consists of one repeated short pattern"));
+    } else if is_sequence(entropy) {
+        findings.push(Finding::new(Severity::Critical, "sequence", "Entropy is an arithmetic sequence of bytes"));
+    } else if longest_byte_run(entropy) >= REPEATED_BYTES {
+        findings.push(Finding::new(Severity::Warning, "repeated_bytes", &format!("Entropy contains {} or more identical bytes in a row", REPEATED_BYTES)));
+    }
+
+    let mut digits = [false; 16];
+    for byte in entropy {
+        digits[(byte >> 4) as usize] = true;
+        digits[(byte & 15) as usize] = true;
+    }
+    let distinct = digits.iter().filter(|used| **used).count();
+    if digits[1..=6].iter().all(|used| *used) && distinct == 6 {
+        findings.push(Finding::new(Severity::Critical, "dice", "Entropy looks like dice rolls typed as hexadecimal, every digit has only 2.6 bits"));
+    } else if distinct <= 6 {
+        findings.push(Finding::new(Severity::Critical, "few_digits", &format!("Entropy uses only {} distinct hexadecimal digits", distinct)));
+    } else if distinct <= 8 {
+        findings.push(Finding::new(Severity::Warning, "few_digits", &format!("Entropy uses only {} distinct hexadecimal digits", distinct)));
+    }
+
+    if monobit_statistic(entropy) > ERFC_LIMIT {
+        findings.push(Finding::new(Severity::Warning, "monobit", "Entropy has unbalanced number of zero and one bits"));
+    }
+    if runs_statistic(entropy).is_some_and(|statistic| statistic > ERFC_LIMIT) {
+        findings.push(Finding::new(Severity::Warning, "runs", "Entropy has unusual number of runs of identical bits"));
+    }
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+    findings
+}
+
+/// Analyze entropy of mnemonic and its words, fails on invalid mnemonic
+pub fn analyze_mnemonic(mnemonic: &str) -> Result<Vec<Finding>, String> {
+    let mut findings = analyze_entropy(&mnemonic_to_entropy(mnemonic)?);
+    let mnemonic = normalize_mnemonic(mnemonic);
+    let words: Vec<&str> = mnemonic.split(' ').collect();
+    let most_repeated = WORD_LIST.iter().map(|word| words.iter().filter(|used| *used == word).count()).max().unwrap_or(0);
+    let distinct = words.len() - words.iter().enumerate().filter(|(position, word)| words[..*position].contains(word)).count();
+    if distinct * 2 <= words.len() {
+        findings.push(Finding::new(Severity::Critical, "repeated_words", &format!("Mnemonic has only {} distinct words", distinct)));
+    } else if most_repeated >= 3 {
+        findings.push(Finding::new(Severity::Warning, "repeated_words", &format!("Mnemonic repeats one word {} times", most_repeated)));
+    }
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::decode_hex;
+
+    fn checks(entropy: &str) -> Vec<(Severity, &'static str)> {
+        analyze_entropy(&decode_hex(entropy).unwrap()).iter().map(|finding| (finding.severity, finding.check)).collect()
+    }
+
+    #[test]
+    fn weak_entropy() {
+        assert_eq!(checks("00000000000000000000000000000000")[..2], [(Severity::Critical, "known_weak"), (Severity::Critical, "repeated_pattern")]);
+        assert!(checks("55555555555555555555555555555555").contains(&(Severity::Critical, "repeated_pattern")));
+        assert!(checks("deadbeefdeadbeefdeadbeefdeadbeef").contains(&(Severity::Critical, "repeated_pattern")));
+        assert!(checks("000102030405060708090a0b0c0d0e0f").contains(&(Severity::Critical, "sequence")));
+        assert!(checks("31425163245361532416345261345216").contains(&(Severity::Critical, "dice")));
+        assert!(checks("9e885d952ad362caeb4efe34a8e91bd2").contains(&(Severity::Critical, "known_weak")));
+        assert!(checks("9e885d952ad36200000000e34a8e91bd").contains(&(Severity::Warning, "repeated_bytes")));
+    }
+
+    #[test]
+    fn statistical_tests() {
+        assert!(checks("fffffffffffffffffffffffffffffffe").contains(&(Severity::Warning, "monobit")));
+        // balanced bits alternating in runs of one, 0xaa and 0x55 only
+        assert!(checks("aa55aa5a5aa5a55aa55a5aa5aa55a55a").contains(&(Severity::Warning, "runs")));
+    }
+
+    #[test]
+    fn random_entropy() {
+        assert_eq!(checks("c55257c360c07c72029aebc1b53c05ed"), []);
+        assert_eq!(checks("3e141609b97933b66a060dcddc71fad1d91677db872031e85f4c015c5e7e8982"), []);
+    }
+
+    #[test]
+    fn repeated_words() {
+        let findings = analyze_mnemonic("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        assert_eq!(findings[0].check, "known_weak");
+        assert!(findings.iter().any(|finding| finding.check == "repeated_words" && finding.severity == Severity::Critical));
+        assert!(analyze_mnemonic("abandon about").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Interactive restore of mnemonic entered word by word
+
+use crate::output::{ErrorCode, Output, Report};
+use crate::passphrase::Source as PassphraseSource;
+use crate::{load_passphrase, to_hex_string};
+use mnemonic::address::{derive_addresses, AddressType, Network};
+use mnemonic::{fingerprint, mnemonic_to_entropy, words_with_prefix, WORD_LIST};
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::MemHistory;
+use rustyline::validate::Validator;
+use rustyline::{Config, Context, Editor, Helper};
+
+/// Number of letters after which words are autocompleted, first four letters identify every word
+const COMPLETION_LENGTH: usize = 3;
+
+/// Input returning to previous word
+const BACK: &str = "<";
+
+/// Words of the list starting with prefix
+fn candidates(prefix: &str) -> Vec<&'static str> {
+    words_with_prefix(prefix).collect()
+}
+
+/// Resolve entered word, unique prefix of at least `COMPLETION_LENGTH` letters is completed
+pub fn complete_word(input: &str) -> Result<&'static str, String> {
+    let input = input.trim().to_lowercase();
+    if let Some(word) = WORD_LIST.iter().find(|word| **word == input) {
+        return Ok(word);
+    }
+    let candidates = if input.len() >= COMPLETION_LENGTH { candidates(&input) } else { Vec::new() };
+    match candidates.len() {
+        0 => Err(format!("Unknown word: {}", input)),
+        1 => Ok(candidates[0]),
+        _ => Err(format!("Ambiguous word {}, could be: {}", input, candidates.join(", "))),
+    }
+}
+
+/// Line editor helper completing words of the list
+struct WordHelper;
+
+impl Completer for WordHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        let prefix = line[..pos].trim_start();
+        if prefix.is_empty() {
+            return Ok((pos, Vec::new()));
+        }
+        Ok((pos - prefix.len(), candidates(prefix).into_iter().map(String::from).collect()))
+    }
+}
+
+impl Hinter for WordHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _: &Context<'_>) -> Option<String> {
+        if pos < line.len() || line.trim().len() < COMPLETION_LENGTH {
+            return None;
+        }
+        complete_word(line).ok()
+            .map(|word| String::from(&word[line.trim().len().min(word.len())..]))
+            .filter(|hint| !hint.is_empty())
+    }
+}
+
+impl Highlighter for WordHelper {}
+
+impl Validator for WordHelper {}
+
+impl Helper for WordHelper {}
+
+/// Ask for words one by one until they form a mnemonic with valid checksum
+///
+/// # Arguments
+///
+/// * `count` - number of words of the mnemonic
+fn read_mnemonic(count: usize) -> Result<String, String> {
+    let config = Config::builder().auto_add_history(false).build();
+    let mut editor = Editor::<WordHelper, MemHistory>::with_history(config, MemHistory::new()).map_err(|error| error.to_string())?;
+    editor.set_helper(Some(WordHelper));
+    eprintln!("Enter words one by one, TAB completes, {} returns to previous word.", BACK);
+
+    let mut words: Vec<&'static str> = Vec::with_capacity(count);
+    loop {
+        if words.len() == count {
+            match mnemonic_to_entropy(&words.join(" ")) {
+                Ok(_) => return Ok(words.join(" ")),
+                Err(error) => {
+                    eprintln!("{} Fix word {} or return to previous words with {}", error, count, BACK);
+                    words.pop();
+                },
+            }
+        }
+        let input = editor.readline(&format!("Word {}/{}: ", words.len() + 1, count)).map_err(|error| format!("Restore aborted: {}", error))?;
+        if input.trim() == BACK {
+            words.pop();
+            continue;
+        }
+        if input.trim().is_empty() {
+            continue;
+        }
+        match complete_word(&input) {
+            Ok(word) => {
+                if word != input.trim() {
+                    eprintln!("Word {}/{}: {}", words.len() + 1, count, word);
+                }
+                words.push(word);
+            },
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+}
+
+/// Handle restore subcommand, shows fingerprint and first address of the restored mnemonic
+///
+/// # Arguments
+///
+/// * `output` - where and how to write the result
+/// * `passphrase` - source of the passphrase
+/// * `count` - number of words of the mnemonic
+pub fn handle_restore(output: &Output, passphrase: &PassphraseSource, count: usize) -> Result<i32, std::io::Error> {
+    let mnemonic = match read_mnemonic(count) {
+        Err(error) => return Ok(output.error(ErrorCode::Io, &error)),
+        Ok(mnemonic) => mnemonic,
+    };
+    let pass_phrase = match load_passphrase(output, passphrase) {
+        Err(code) => return Ok(code),
+        Ok(pass_phrase) => pass_phrase,
+    };
+    let seed = output.seed_params.mnemonic_to_seed(&mnemonic, Some(&pass_phrase));
+    let derived = fingerprint(&seed).and_then(|fingerprint| {
+        let path = AddressType::P2wpkh.default_path(Network::Mainnet);
+        let addresses = derive_addresses(&seed, &path, AddressType::P2wpkh, Network::Mainnet, 1)?;
+        Ok((to_hex_string(fingerprint.to_vec()), addresses))
+    });
+    let (fingerprint, addresses) = match derived {
+        Err(error) => return Ok(output.error(ErrorCode::InvalidDerivation, &format!("Input error: {}", error))),
+        Ok(derived) => derived,
+    };
+    if let Err(code) = output.check_fingerprint(&fingerprint) {
+        return Ok(code);
+    }
+
+    let mut report = Report::new("restore");
+    report.add_result("Fingerprint", "fingerprint", &fingerprint);
+    for (path, address) in addresses {
+        report.add(&format!("Address {}", path), "address", &address);
+        report.add_json("path", serde_json::Value::from(path));
+    }
+    report.add_mnemonic_info(&mnemonic);
+    output.write(&report)?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_completion() {
+        assert_eq!(complete_word("abandon"), Ok("abandon"));
+        assert_eq!(complete_word(" Zoo\n"), Ok("zoo"));
+        assert_eq!(complete_word("aban"), Ok("abandon"));
+        assert_eq!(complete_word("zeb"), Ok("zebra"));
+        assert!(complete_word("abs").unwrap_err().starts_with("Ambiguous"));
+        assert!(complete_word("ab").is_err());
+        assert!(complete_word("qwerty").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Parameters of PBKDF2 deriving the seed from mnemonic
+//!
+//! BIP-39 uses the salt `"mnemonic"` followed by the passphrase, 2048 iterations of HMAC-SHA512 and 64 bytes of output.
+//! Some wallets used the BIP-39 words with other parameters, their seeds can be recovered with parameters set one by one.
+//! Electrum is the only preset besides BIP-39: the seed of its 2.0 native seed phrases is checked against a case of
+//! Electrum's own test suite, presets of other wallets could not be checked against their implementation. Electrum
+//! normalizes phrases by its own rules, which agree with NFKD only for phrases of unaccented lowercase words. Wallets
+//! which feed the entropy instead of the mnemonic to PBKDF2, like Cardano Icarus or Polkadot, do not fit these
+//! parameters at all.
+
+use crate::{hmac_sha256, hmac_sha512, normalize_mnemonic, pbkdf2_with};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Pseudorandom function of PBKDF2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prf {
+    HmacSha512,
+    HmacSha256,
+}
+
+impl Prf {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Prf::HmacSha512 => "hmac-sha512",
+            Prf::HmacSha256 => "hmac-sha256",
+        }
+    }
+}
+
+impl core::str::FromStr for Prf {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Prf, String> {
+        match name {
+            "hmac-sha512" => Ok(Prf::HmacSha512),
+            "hmac-sha256" => Ok(Prf::HmacSha256),
+            _ => Err(format!("Unknown PRF: {}", name)),
+        }
+    }
+}
+
+/// Known seed derivations, each checked against a published test vector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeedPreset {
+    /// BIP-39
+    Standard,
+    /// Electrum 2.0 native seed phrase normalization salt `"electrum"`
+    Electrum,
+}
+
+impl core::str::FromStr for SeedPreset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<SeedPreset, String> {
+        match name {
+            "standard" => Ok(SeedPreset::Standard),
+            "electrum" => Ok(SeedPreset::Electrum),
+            _ => Err(format!("Unknown seed preset: {}", name)),
+        }
+    }
+}
+
+/// Parameters of seed derivation, BIP-39 by default, e.g. `SeedDerivationParams::new().iterations(10000).out_len(32)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedDerivationParams {
+    /// Salt preceding the passphrase
+    pub salt_prefix: String,
+    pub iterations: u32,
+    pub prf: Prf,
+    /// Length of the seed in bytes
+    pub out_len: usize,
+}
+
+impl Default for SeedDerivationParams {
+    fn default() -> SeedDerivationParams {
+        SeedDerivationParams { salt_prefix: String::from("mnemonic"), iterations: 2048, prf: Prf::HmacSha512, out_len: 64 }
+    }
+}
+
+impl From<SeedPreset> for SeedDerivationParams {
+    fn from(preset: SeedPreset) -> SeedDerivationParams {
+        match preset {
+            SeedPreset::Standard => SeedDerivationParams::default(),
+            SeedPreset::Electrum => SeedDerivationParams::new().salt_prefix("electrum"),
+        }
+    }
+}
+
+impl core::fmt::Display for SeedDerivationParams {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "salt prefix \"{}\", {} iterations of {}, {} bytes", self.salt_prefix, self.iterations, self.prf.as_str(), self.out_len)
+    }
+}
+
+impl SeedDerivationParams {
+    /// Parameters of BIP-39
+    pub fn new() -> SeedDerivationParams {
+        SeedDerivationParams::default()
+    }
+
+    pub fn salt_prefix(mut self, salt_prefix: &str) -> SeedDerivationParams {
+        self.salt_prefix = String::from(salt_prefix);
+        self
+    }
+
+    /// Set number of iterations, at least one iteration is always done
+    pub fn iterations(mut self, iterations: u32) -> SeedDerivationParams {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn prf(mut self, prf: Prf) -> SeedDerivationParams {
+        self.prf = prf;
+        self
+    }
+
+    pub fn out_len(mut self, out_len: usize) -> SeedDerivationParams {
+        self.out_len = out_len;
+        self
+    }
+
+    /// Whether the parameters are those of BIP-39
+    pub fn is_standard(&self) -> bool {
+        *self == SeedDerivationParams::default()
+    }
+
+    /// Transform a mnemonic to a seed, the mnemonic is normalized first, see `normalize_mnemonic`
+    ///
+    /// # Arguments
+    ///
+    ///  * `mnemonic` - the mnemonic
+    ///  * `passphrase` - an optional passphrase
+    pub fn mnemonic_to_seed(&self, mnemonic: &str, passphrase: Option<&str>) -> Vec<u8> {
+        let salt = format!("{}{}", self.salt_prefix, passphrase.unwrap_or(""));
+        let prf = match self.prf {
+            Prf::HmacSha512 => hmac_sha512,
+            Prf::HmacSha256 => hmac_sha256,
+        };
+        let mnemonic = normalize_mnemonic(mnemonic);
+        pbkdf2_with(prf, mnemonic.as_bytes(), salt.as_bytes(), self.iterations as usize, self.out_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mnemonic_to_seed;
+    use crate::util::decode_hex;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn presets() {
+        assert_eq!(SeedDerivationParams::from(SeedPreset::Standard).mnemonic_to_seed(MNEMONIC, Some("TREZOR")), mnemonic_to_seed(MNEMONIC, Some("TREZOR")));
+        let electrum = SeedDerivationParams::from(SeedPreset::Electrum);
+        assert_eq!(electrum, SeedDerivationParams { salt_prefix: String::from("electrum"), iterations: 2048, prf: Prf::HmacSha512, out_len: 64 });
+        assert!(!electrum.is_standard());
+        // the salt is a plain prefix of the passphrase
+        assert_eq!(electrum.mnemonic_to_seed(MNEMONIC, Some("TREZOR")), SeedDerivationParams::new().salt_prefix("electrumTREZOR").mnemonic_to_seed(MNEMONIC, None));
+        // Electrum's electrum/tests/test_mnemonic.py, test case 'english' of SEED_TEST_CASES
+        let seed = electrum.mnemonic_to_seed("wild father tree among universe such mobile favorite target dynamic credit identify", None);
+        assert_eq!(seed, decode_hex("aac2a6302e48577ab4b46f23dbae0774e2e62c796f797d0a1b5faeb528301e3064342dafb79069e7c4c6b8c38ae11d7a973bec0d4f70626f8cc5184a8d0b0756").unwrap());
+        assert_eq!("electrum".parse::<SeedPreset>(), Ok(SeedPreset::Electrum));
+        assert!("coinomi".parse::<SeedPreset>().is_err());
+    }
+
+    #[test]
+    fn normalized_mnemonic() {
+        let messy = format!(" {}\n", MNEMONIC.to_uppercase().replace(' ', "\t  "));
+        assert_eq!(mnemonic_to_seed(&messy, Some("TREZOR")), mnemonic_to_seed(MNEMONIC, Some("TREZOR")));
+        let electrum = SeedDerivationParams::from(SeedPreset::Electrum);
+        assert_eq!(electrum.mnemonic_to_seed(&messy, None), electrum.mnemonic_to_seed(MNEMONIC, None));
+    }
+
+    #[test]
+    fn custom_params() {
+        // RFC 7914 PBKDF2-HMAC-SHA256 test vector
+        let params = SeedDerivationParams::new().salt_prefix("salt").iterations(1).prf(Prf::HmacSha256).out_len(64);
+        assert_eq!(params.mnemonic_to_seed("passwd", None), decode_hex("55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783").unwrap());
+        let short = SeedDerivationParams::new().iterations(4096).out_len(32).mnemonic_to_seed(MNEMONIC, None);
+        assert_eq!(short.len(), 32);
+        assert_ne!(short[..], mnemonic_to_seed(MNEMONIC, None)[..32]);
+    }
+}
@@ -0,0 +1,179 @@
+//! SeedQR codes of mnemonics imported by camera into signing devices
+//!
+//! Standard SeedQR holds 4-digit zero-padded word indices in numeric mode, CompactSeedQR holds raw entropy in byte mode.
+//! Both use the smallest QR version fitting the data with error correction level L.
+
+use crate::{entropy_to_mnemonic, mnemonic_lookup, mnemonic_to_entropy, normalize_mnemonic, WORD_LIST};
+use qrcode::bits::Bits;
+use qrcode::render::{svg, unicode};
+use qrcode::{EcLevel, QrCode, Version};
+
+/// Size of one module of PNG and SVG images in pixels
+const MODULE_SIZE: usize = 10;
+/// Width of light border around the code in modules
+const QUIET_ZONE: usize = 4;
+/// Black on white, so the code scans on dark terminals too
+const ANSI_COLORS: &str = "\x1b[30;47m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Payload format of SeedQR
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeedQrFormat {
+    /// Word indices as decimal digits
+    Standard,
+    /// Entropy bytes
+    Compact,
+}
+
+impl std::str::FromStr for SeedQrFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<SeedQrFormat, String> {
+        match name {
+            "standard" => Ok(SeedQrFormat::Standard),
+            "compact" => Ok(SeedQrFormat::Compact),
+            _ => Err(format!("Unknown SeedQR format: {}", name)),
+        }
+    }
+}
+
+/// Payload of SeedQR code of mnemonic, digits for standard format or entropy for compact format
+pub fn payload(mnemonic: &str, format: SeedQrFormat) -> Result<Vec<u8>, String> {
+    let entropy = mnemonic_to_entropy(mnemonic)?;
+    match format {
+        SeedQrFormat::Compact => Ok(entropy),
+        SeedQrFormat::Standard => {
+            let indices = normalize_mnemonic(mnemonic).split(' ').map(mnemonic_lookup).collect::<Result<Vec<u16>, String>>()?;
+            Ok(indices.iter().map(|index| format!("{:04}", index)).collect::<String>().into_bytes())
+        },
+    }
+}
+
+/// Decode mnemonic from payload of scanned SeedQR code, format is given by payload length
+pub fn decode(payload: &[u8]) -> Result<String, String> {
+    let mnemonic = decode_words(payload)?;
+    mnemonic_to_entropy(&mnemonic)?;
+    Ok(mnemonic)
+}
+
+/// Decode words of payload like `decode`, the checksum of standard payload is not verified
+pub fn decode_words(payload: &[u8]) -> Result<String, String> {
+    match payload.len() {
+        16 | 20 | 24 | 28 | 32 => entropy_to_mnemonic(payload).map_err(String::from),
+        48 | 60 | 72 | 84 | 96 => {
+            let digits = std::str::from_utf8(payload).ok().filter(|digits| digits.bytes().all(|digit| digit.is_ascii_digit()))
+                .ok_or_else(|| String::from("Standard SeedQR payload contains non-digit characters!"))?;
+            let words = (0..digits.len()).step_by(4).map(|position| {
+                let index: usize = digits[position..position + 4].parse().unwrap();
+                WORD_LIST.get(index).copied().ok_or_else(|| format!("Invalid word index: {}", index))
+            }).collect::<Result<Vec<&str>, String>>()?;
+            Ok(words.join(" "))
+        },
+        length => Err(format!("SeedQR payload cannot have {} bytes!", length)),
+    }
+}
+
+/// Encode data in given mode into QR code of the smallest fitting version
+fn encode(data: &[u8], format: SeedQrFormat) -> Result<QrCode, String> {
+    for version in 1..=40 {
+        let mut bits = Bits::new(Version::Normal(version));
+        let pushed = match format {
+            SeedQrFormat::Standard => bits.push_numeric_data(data),
+            SeedQrFormat::Compact => bits.push_byte_data(data),
+        };
+        if pushed.and_then(|_| bits.push_terminator(EcLevel::L)).is_ok() {
+            return QrCode::with_bits(bits, EcLevel::L).map_err(|error| error.to_string());
+        }
+    }
+    Err(String::from("Data do not fit into QR code!"))
+}
+
+/// SeedQR code of mnemonic
+pub fn qr_code(mnemonic: &str, format: SeedQrFormat) -> Result<QrCode, String> {
+    encode(&payload(mnemonic, format)?, format)
+}
+
+/// Render code as Unicode half blocks colored by ANSI escapes, two rows of modules per line
+pub fn to_terminal(code: &QrCode) -> String {
+    let image = code.render::<unicode::Dense1x2>().quiet_zone(true).build();
+    image.lines().map(|line| format!("{}{}{}\n", ANSI_COLORS, line, ANSI_RESET)).collect()
+}
+
+/// Render code as SVG image
+pub fn to_svg(code: &QrCode) -> String {
+    code.render::<svg::Color>().module_dimensions(MODULE_SIZE as u32, MODULE_SIZE as u32).build()
+}
+
+/// Render code as 8-bit grayscale PNG image
+pub fn to_png(code: &QrCode) -> Result<Vec<u8>, String> {
+    let modules = code.width() + 2 * QUIET_ZONE;
+    let size = modules * MODULE_SIZE;
+    let colors = code.to_colors();
+    let mut pixels = vec![0xffu8; size * size];
+    for y in 0..code.width() {
+        for x in 0..code.width() {
+            if colors[y * code.width() + x] == qrcode::Color::Light {
+                continue;
+            }
+            for row in 0..MODULE_SIZE {
+                let start = ((y + QUIET_ZONE) * MODULE_SIZE + row) * size + (x + QUIET_ZONE) * MODULE_SIZE;
+                pixels[start..start + MODULE_SIZE].fill(0);
+            }
+        }
+    }
+
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|error| error.to_string())?;
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    // SeedQR specification test vector
+    const MNEMONIC_24: &str = "attack pizza motion avocado network gather crop fresh patrol unusual wild holiday candy pony ranch winter theme error hybrid van cereal salon goddess expire";
+    const DIGITS_24: &str = "011513251154012711900771041507421289190620080870026613431420201617920614089619290300152408010643";
+
+    #[test]
+    fn standard_payload() {
+        assert_eq!(payload(MNEMONIC, SeedQrFormat::Standard).unwrap(), format!("{}0003", "0000".repeat(11)).into_bytes());
+        assert_eq!(payload(MNEMONIC_24, SeedQrFormat::Standard).unwrap(), DIGITS_24.as_bytes());
+        assert_eq!(decode(DIGITS_24.as_bytes()).unwrap(), MNEMONIC_24);
+        assert_eq!(qr_code(MNEMONIC, SeedQrFormat::Standard).unwrap().version(), Version::Normal(2));
+        assert_eq!(qr_code(MNEMONIC_24, SeedQrFormat::Standard).unwrap().version(), Version::Normal(3));
+    }
+
+    #[test]
+    fn compact_payload() {
+        assert_eq!(payload(MNEMONIC, SeedQrFormat::Compact).unwrap(), vec![0u8; 16]);
+        let entropy = payload(MNEMONIC_24, SeedQrFormat::Compact).unwrap();
+        assert_eq!(decode(&entropy).unwrap(), MNEMONIC_24);
+        assert_eq!(qr_code(MNEMONIC, SeedQrFormat::Compact).unwrap().version(), Version::Normal(1));
+        assert_eq!(qr_code(MNEMONIC_24, SeedQrFormat::Compact).unwrap().version(), Version::Normal(2));
+    }
+
+    #[test]
+    fn invalid_payloads() {
+        assert!(decode(b"0000").is_err());
+        assert!(decode(&[b'x'; 48]).is_err());
+        assert!(decode(format!("{}2048", "0000".repeat(11)).as_bytes()).is_err());
+        assert!(decode("0000".repeat(12).as_bytes()).unwrap_err().contains("checksum"));
+    }
+
+    #[test]
+    fn render_images() {
+        let code = qr_code(MNEMONIC, SeedQrFormat::Compact).unwrap();
+        let terminal = to_terminal(&code);
+        // 21 modules with quiet zone of 4 modules on both sides, two rows per line
+        assert_eq!(terminal.lines().count(), 15);
+        assert!(to_svg(&code).starts_with("<?xml"));
+        assert!(to_png(&code).unwrap().starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+}
@@ -0,0 +1,271 @@
+use ed25519_dalek::SigningKey;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::{Field, PrimeField};
+use crate::bip32::HARDENED;
+use crate::hmac_sha512;
+
+/// Curve supported by SLIP-0010
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Secp256k1,
+    Nist256p1,
+    Ed25519,
+}
+
+impl std::str::FromStr for Curve {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Curve, String> {
+        match name {
+            "secp256k1" => Ok(Curve::Secp256k1),
+            "nist256p1" | "p256" | "secp256r1" => Ok(Curve::Nist256p1),
+            "ed25519" => Ok(Curve::Ed25519),
+            _ => Err(format!("Unknown curve: {}", name)),
+        }
+    }
+}
+
+impl Curve {
+    /// HMAC key of master key generation
+    fn seed_key(self) -> &'static [u8] {
+        match self {
+            Curve::Secp256k1 => b"Bitcoin seed",
+            Curve::Nist256p1 => b"Nist256p1 seed",
+            Curve::Ed25519 => b"ed25519 seed",
+        }
+    }
+
+    /// Compute `tweak + key` modulo curve order, None if tweak is out of range or result is zero
+    ///
+    /// Ed25519 keys are not tweaked, the tweak itself is the child key.
+    fn add_private_keys(self, tweak: &[u8], key: &[u8; 32]) -> Option<[u8; 32]> {
+        let mut result = [0u8; 32];
+        match self {
+            Curve::Secp256k1 => {
+                let tweak: Option<k256::Scalar> = k256::Scalar::from_repr(k256::FieldBytes::clone_from_slice(tweak)).into();
+                let sum = tweak? + k256::Scalar::from_repr(k256::FieldBytes::clone_from_slice(key)).unwrap();
+                if bool::from(sum.is_zero()) {
+                    return None;
+                }
+                result.copy_from_slice(&sum.to_bytes());
+            },
+            Curve::Nist256p1 => {
+                let tweak: Option<p256::Scalar> = p256::Scalar::from_repr(p256::FieldBytes::clone_from_slice(tweak)).into();
+                let sum = tweak? + p256::Scalar::from_repr(p256::FieldBytes::clone_from_slice(key)).unwrap();
+                if bool::from(sum.is_zero()) {
+                    return None;
+                }
+                result.copy_from_slice(&sum.to_bytes());
+            },
+            Curve::Ed25519 => result.copy_from_slice(tweak),
+        }
+        Some(result)
+    }
+
+    /// Check that master key candidate is a valid private key
+    fn is_valid_master(self, key: &[u8]) -> bool {
+        let zero = [0u8; 32];
+        match self {
+            Curve::Ed25519 => true,
+            _ => self.add_private_keys(key, &zero).is_some(),
+        }
+    }
+
+    /// Compute public key, compressed point for ECDSA curves and 0x00 followed by the key for ed25519
+    pub fn public_key(self, private_key: &[u8; 32]) -> Result<Vec<u8>, String> {
+        match self {
+            Curve::Secp256k1 => {
+                let scalar: Option<k256::Scalar> = k256::Scalar::from_repr(k256::FieldBytes::clone_from_slice(private_key)).into();
+                match scalar {
+                    Some(scalar) if !bool::from(scalar.is_zero()) => {
+                        let point = (k256::ProjectivePoint::GENERATOR * scalar).to_affine();
+                        Ok(point.to_encoded_point(true).as_bytes().to_vec())
+                    },
+                    _ => Err(String::from("Invalid private key!")),
+                }
+            },
+            Curve::Nist256p1 => {
+                let scalar: Option<p256::Scalar> = p256::Scalar::from_repr(p256::FieldBytes::clone_from_slice(private_key)).into();
+                match scalar {
+                    Some(scalar) if !bool::from(scalar.is_zero()) => {
+                        let point = (p256::ProjectivePoint::GENERATOR * scalar).to_affine();
+                        Ok(point.to_encoded_point(true).as_bytes().to_vec())
+                    },
+                    _ => Err(String::from("Invalid private key!")),
+                }
+            },
+            Curve::Ed25519 => {
+                let mut result = vec![0u8];
+                result.extend_from_slice(SigningKey::from_bytes(private_key).verifying_key().as_bytes());
+                Ok(result)
+            },
+        }
+    }
+}
+
+/// SLIP-0010 extended private key
+#[derive(Clone, PartialEq)]
+pub struct ExtendedKey {
+    curve: Curve,
+    private_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl ExtendedKey {
+    /// Create master key from seed
+    pub fn new_master(curve: Curve, seed: &[u8]) -> ExtendedKey {
+        let mut output = hmac_sha512(seed, curve.seed_key());
+        while !curve.is_valid_master(&output[..32]) {
+            output = hmac_sha512(&output, curve.seed_key());
+        }
+        ExtendedKey::from_hmac(curve, &output[..32], &output[32..])
+    }
+
+    fn from_hmac(curve: Curve, private_key: &[u8], chain_code: &[u8]) -> ExtendedKey {
+        let mut key = ExtendedKey { curve, private_key: [0u8; 32], chain_code: [0u8; 32] };
+        key.private_key.copy_from_slice(private_key);
+        key.chain_code.copy_from_slice(chain_code);
+        key
+    }
+
+    /// Derive child key with given index, ed25519 supports only hardened indices
+    pub fn derive_child(&self, index: u32) -> Result<ExtendedKey, String> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED {
+            data.push(0);
+            data.extend_from_slice(&self.private_key);
+        } else if self.curve == Curve::Ed25519 {
+            return Err(String::from("Ed25519 supports only hardened derivation!"));
+        } else {
+            data.extend_from_slice(&self.public_key());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+
+        loop {
+            let output = hmac_sha512(&data, &self.chain_code);
+            if let Some(private_key) = self.curve.add_private_keys(&output[..32], &self.private_key) {
+                return Ok(ExtendedKey::from_hmac(self.curve, &private_key, &output[32..]));
+            }
+            // invalid key, retry with 0x01 || IR || index
+            data = vec![1];
+            data.extend_from_slice(&output[32..]);
+            data.extend_from_slice(&index.to_be_bytes());
+        }
+    }
+
+    /// Derive key along the path given by indices
+    pub fn derive_path(&self, indices: &[u32]) -> Result<ExtendedKey, String> {
+        let mut key = self.clone();
+        for &index in indices {
+            key = key.derive_child(index)?;
+        }
+        Ok(key)
+    }
+
+    /// Curve of the key
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    /// Private key
+    pub fn private_key(&self) -> &[u8; 32] {
+        &self.private_key
+    }
+
+    /// Chain code
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Public key, 33 bytes for all curves
+    pub fn public_key(&self) -> Vec<u8> {
+        self.curve.public_key(&self.private_key).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::parse_path;
+    use crate::util::decode_hex;
+
+    fn check(curve: Curve, seed: &str, path: &str, chain_code: &str, private_key: &str, public_key: &str) {
+        let master = ExtendedKey::new_master(curve, &decode_hex(seed).unwrap());
+        let key = master.derive_path(&parse_path(path).unwrap()).unwrap();
+        assert_eq!(key.chain_code().to_vec(), decode_hex(chain_code).unwrap());
+        assert_eq!(key.private_key().to_vec(), decode_hex(private_key).unwrap());
+        assert_eq!(key.public_key(), decode_hex(public_key).unwrap());
+    }
+
+    #[test]
+    fn slip10_ed25519_tv1() {
+        let seed = "000102030405060708090a0b0c0d0e0f";
+        check(Curve::Ed25519, seed, "m",
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            "00a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed");
+        check(Curve::Ed25519, seed, "m/0'/1'/2'/2'/1000000000'",
+            "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            "003c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a");
+    }
+
+    #[test]
+    fn slip10_nist256p1_tv1() {
+        let seed = "000102030405060708090a0b0c0d0e0f";
+        check(Curve::Nist256p1, seed, "m",
+            "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+            "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+            "0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8");
+    }
+
+    #[test]
+    fn slip10_nist256p1_tv2() {
+        let seed = "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542";
+        check(Curve::Nist256p1, seed, "m",
+            "96cd4465a9644e31528eda3592aa35eb39a9527769ce1855beafc1b81055e75d",
+            "eaa31c2e46ca2962227cf21d73a7ef0ce8b31c756897521eb6c7b39796633357",
+            "02c9e16154474b3ed5b38218bb0463e008f89ee03e62d22fdcc8014beab25b48fa");
+        check(Curve::Nist256p1, seed, "m/0",
+            "84e9c258bb8557a40e0d041115b376dd55eda99c0042ce29e81ebe4efed9b86a",
+            "d7d065f63a62624888500cdb4f88b6d59c2927fee9e6d0cdff9cad555884df6e",
+            "039b6df4bece7b6c81e2adfeea4bcf5c8c8a6e40ea7ffa3cf6e8494c61a1fc82cc");
+    }
+
+    #[test]
+    fn slip10_nist256p1_derivation_retry() {
+        let seed = "000102030405060708090a0b0c0d0e0f";
+        check(Curve::Nist256p1, seed, "m/28578'",
+            "e94c8ebe30c2250a14713212f6449b20f3329105ea15b652ca5bdfc68f6c65c2",
+            "06f0db126f023755d0b8d86d4591718a5210dd8d024e3e14b6159d63f53aa669",
+            "02519b5554a4872e8c9c1c847115363051ec43e93400e030ba3c36b52a3e70a5b7");
+        check(Curve::Nist256p1, seed, "m/28578'/33941",
+            "9e87fe95031f14736774cd82f25fd885065cb7c358c1edf813c72af535e83071",
+            "092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a",
+            "0235bfee614c0d5b2cae260000bb1d0d84b270099ad790022c1ae0b2e782efe120");
+    }
+
+    #[test]
+    fn slip10_nist256p1_seed_retry() {
+        check(Curve::Nist256p1, "a7305bc8df8d0951f0cb224c0e95d7707cbdf2c6ce7e8d481fec69c7ff5e9446", "m",
+            "7762f9729fed06121fd13f326884c82f59aa95c57ac492ce8c9654e60efd130c",
+            "3b8c18469a4634517d6d0b65448f8e6c62091b45540a1743c5846be55d47d88f",
+            "0383619fadcde31063d8c5cb00dbfe1713f3e6fa169d8541a798752a1c1ca0cb20");
+    }
+
+    #[test]
+    fn secp256k1_matches_bip32() {
+        let seed = decode_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let path = parse_path("m/0'/1/2'").unwrap();
+        let slip10 = ExtendedKey::new_master(Curve::Secp256k1, &seed).derive_path(&path).unwrap();
+        let bip32 = crate::bip32::ExtendedPrivateKey::new_master(&seed).unwrap().derive_path(&path).unwrap();
+        assert_eq!(slip10.private_key(), bip32.private_key());
+        assert_eq!(slip10.public_key(), bip32.public_key().to_vec());
+    }
+
+    #[test]
+    fn ed25519_non_hardened() {
+        let master = ExtendedKey::new_master(Curve::Ed25519, &[0u8; 16]);
+        assert!(master.derive_child(1).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Strength estimation and policy of BIP-39 passphrases
+//!
+//! The estimate follows zxcvbn: the passphrase is split into dictionary words, repeats, sequences, keyboard runs,
+//! years and brute forced characters, so that the total number of guesses is minimal. Words of the BIP-39 list
+//! count as dictionary words, since attackers who know the mnemonic try them first.
+
+use crate::quality::{Finding, Severity};
+use crate::{mnemonic_lookup, WORD_LIST};
+use unicode_normalization::UnicodeNormalization;
+
+/// Frequent passwords, earlier ones are guessed first
+const COMMON: [&str; 40] = [
+    "123456", "password", "12345678", "qwerty", "123456789", "12345", "1234", "111111", "1234567", "dragon",
+    "123123", "baseball", "abc123", "football", "monkey", "letmein", "shadow", "master", "696969", "michael",
+    "mustang", "666666", "qwertyuiop", "123321", "1234567890", "pussy", "superman", "654321", "trustno1", "iloveyou",
+    "sunshine", "princess", "welcome", "secret", "bitcoin", "satoshi", "trezor", "ledger", "passphrase", "mnemonic",
+];
+/// Rows of keyboard, runs along them are easy to guess
+const KEYBOARD: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+/// Guesses of any match of more characters, from zxcvbn
+const MIN_GUESSES: f64 = 50.0;
+/// Characters of passphrase which are analysed, the rest is ignored as in zxcvbn to bound the quadratic search
+const MAX_LENGTH: usize = 100;
+/// Length of longest word of both dictionaries, longer tokens are not looked up
+const MAX_WORD_LENGTH: usize = 10;
+/// Passphrases below this estimate are reported as weak
+const WEAK_BITS: f64 = 40.0;
+
+/// Strength estimate of passphrase
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    /// Base 2 logarithm of number of guesses
+    pub bits: f64,
+    /// zxcvbn score from 0 (too guessable) to 4 (very unguessable)
+    pub score: u8,
+}
+
+/// Characters allowed in passphrase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Charset {
+    Any,
+    /// Printable ASCII, which can be typed on every keyboard
+    PrintableAscii,
+    /// Unicode which NFKD normalization does not change
+    NfkdStable,
+}
+
+impl std::str::FromStr for Charset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Charset, String> {
+        match name {
+            "any" => Ok(Charset::Any),
+            "ascii" => Ok(Charset::PrintableAscii),
+            "nfkd" => Ok(Charset::NfkdStable),
+            _ => Err(format!("Unknown passphrase charset: {}", name)),
+        }
+    }
+}
+
+/// Requirements on passphrase, violations are critical findings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    /// Minimal estimated strength in bits
+    pub min_bits: f64,
+    /// Reject leading and trailing whitespace
+    pub reject_whitespace: bool,
+    pub charset: Charset,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy { min_bits: 0.0, reject_whitespace: false, charset: Charset::Any }
+    }
+}
+
+/// Number of possible characters of the class of character
+fn cardinality(character: char) -> f64 {
+    match character {
+        'a'..='z' | 'A'..='Z' => 26.0,
+        '0'..='9' => 10.0,
+        ' '..='~' => 33.0,
+        _ => 100.0,
+    }
+}
+
+/// Guesses of dictionary word with given capitalization
+fn dictionary_guesses(token: &str) -> Option<f64> {
+    let lowercase = token.to_lowercase();
+    let rank = match COMMON.iter().position(|word| *word == lowercase) {
+        Some(position) => position as f64 + 1.0,
+        None if mnemonic_lookup(&lowercase).is_ok() => WORD_LIST.len() as f64,
+        None => return None,
+    };
+    let uppercase = token.chars().filter(|character| character.is_uppercase()).count();
+    let first_upper = token.chars().next().is_some_and(char::is_uppercase) && uppercase == 1;
+    let variations = match uppercase {
+        0 => 1.0,
+        _ if first_upper || uppercase == token.chars().count() => 2.0,
+        _ => 2f64.powi(uppercase as i32),
+    };
+    Some(rank * variations)
+}
+
+/// Guesses of token if it matches some pattern, None otherwise
+fn pattern_guesses(token: &[char]) -> Option<f64> {
+    let length = token.len() as f64;
+    let text: String = token.iter().collect();
+    let mut guesses = if token.len() <= MAX_WORD_LENGTH { dictionary_guesses(&text) } else { None };
+    if token.iter().all(|character| *character == token[0]) {
+        guesses = Some(guesses.map_or(cardinality(token[0]) * length, |other| other.min(cardinality(token[0]) * length)));
+    }
+    let steps: Vec<i64> = token.windows(2).map(|pair| pair[1] as i64 - pair[0] as i64).collect();
+    if steps.iter().all(|step| *step == steps[0] && step.abs() == 1) {
+        let direction = if steps[0] < 0 { 2.0 } else { 1.0 };
+        guesses = Some(guesses.map_or(f64::MAX, |other| other).min(cardinality(token[0]) * length * direction));
+    }
+    let lowercase = text.to_lowercase();
+    if KEYBOARD.iter().any(|row| row.contains(&lowercase) || row.chars().rev().collect::<String>().contains(&lowercase)) {
+        guesses = Some(guesses.map_or(f64::MAX, |other| other).min(KEYBOARD.len() as f64 * 10.0 * length));
+    }
+    if token.len() == 4 && text.parse::<u32>().is_ok_and(|year| (1900..=2099).contains(&year)) {
+        guesses = Some(guesses.map_or(f64::MAX, |other| other).min(200.0));
+    }
+    guesses.map(|guesses| guesses.max(MIN_GUESSES))
+}
+
+/// Estimate strength of passphrase
+pub fn estimate(passphrase: &str) -> Estimate {
+    let characters: Vec<char> = passphrase.chars().take(MAX_LENGTH).collect();
+    // minimal bits of prefix of given length
+    let mut best = vec![0f64; characters.len() + 1];
+    for end in 1..=characters.len() {
+        best[end] = best[end - 1] + cardinality(characters[end - 1]).log2();
+        for start in (0..end - 1).rev() {
+            if let Some(guesses) = pattern_guesses(&characters[start..end]) {
+                best[end] = best[end].min(best[start] + guesses.log2());
+            }
+        }
+    }
+    let bits = best[characters.len()];
+    let score = [10.0, 20.0, 26.6, 33.2].iter().filter(|threshold| bits >= **threshold).count() as u8;
+    Estimate { bits, score }
+}
+
+/// Check passphrase against policy, reports also problems the policy does not forbid as warnings
+///
+/// Empty passphrase means no passphrase and is not checked.
+pub fn check_passphrase(passphrase: &str, policy: &Policy) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if passphrase.is_empty() {
+        return findings;
+    }
+    let finding = |critical: bool, check: &'static str, message: String| {
+        Finding { severity: if critical { Severity::Critical } else { Severity::Warning }, check, message }
+    };
+
+    let strength = estimate(passphrase);
+    if strength.bits < policy.min_bits || strength.bits < WEAK_BITS {
+        let message = format!("Passphrase is weak, its estimated strength is {:.0} bits", strength.bits);
+        findings.push(finding(strength.bits < policy.min_bits, "strength", message));
+    }
+    if passphrase.trim() != passphrase {
+        findings.push(finding(policy.reject_whitespace, "whitespace", String::from("Passphrase starts or ends with whitespace, which is easy to lose")));
+    }
+    if passphrase.chars().any(char::is_control) {
+        findings.push(finding(policy.charset != Charset::Any, "control", String::from("Passphrase contains control characters")));
+    }
+    if passphrase.nfkd().ne(passphrase.chars()) {
+        let message = String::from("Passphrase contains characters changed by NFKD normalization, wallets which do not normalize derive different seed");
+        findings.push(finding(policy.charset != Charset::Any, "normalization", message));
+    } else if !passphrase.is_ascii() {
+        let message = String::from("Passphrase contains non-ASCII characters, which may be hard to type on other keyboards");
+        findings.push(finding(policy.charset == Charset::PrintableAscii, "non_ascii", message));
+    }
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(passphrase: &str, policy: &Policy) -> Vec<(Severity, &'static str)> {
+        check_passphrase(passphrase, policy).iter().map(|finding| (finding.severity, finding.check)).collect()
+    }
+
+    #[test]
+    fn strength_estimate() {
+        assert_eq!(estimate("").bits, 0.0);
+        assert!(estimate("password").bits < 10.0);
+        assert!(estimate("Password1").bits < 20.0);
+        assert!(estimate("aaaaaaaaaaaaaaaaaaaa").bits < 10.0);
+        assert!(estimate("abcdefghijklmnop").bits < 10.0);
+        assert!(estimate("qwertyuiop1990").bits < 20.0);
+        // four random words of the list give 44 bits, separators add some more
+        let words = estimate("zebra cradle ozone drill");
+        assert!(words.bits > 44.0 && words.bits < 65.0);
+        assert_eq!(words.score, 4);
+        assert!(estimate("x7#Rq9!vLp2@Kd8$").bits > 70.0);
+    }
+
+    #[test]
+    fn long_passphrase() {
+        let passphrase = "zebra cradle ozone drill ".repeat(400);
+        let prefix: String = passphrase.chars().take(MAX_LENGTH).collect();
+        assert_eq!(estimate(&passphrase), estimate(&prefix));
+        assert!(estimate(&"x7#Rq9!vLp2@Kd8$".repeat(1000)).bits > 128.0);
+    }
+
+    #[test]
+    fn policy_violations() {
+        let policy = Policy::default();
+        assert_eq!(checks("", &policy), []);
+        assert_eq!(checks("correct horse battery staple 42", &policy), []);
+        assert_eq!(checks("TREZOR", &policy), [(Severity::Warning, "strength")]);
+        assert_eq!(checks("correct horse battery staple 42 ", &policy), [(Severity::Warning, "whitespace")]);
+        let strict = Policy { min_bits: 128.0, reject_whitespace: true, charset: Charset::PrintableAscii };
+        assert_eq!(checks("correct horse battery staple 42 ", &strict), [(Severity::Critical, "strength"), (Severity::Critical, "whitespace")]);
+        assert!(checks("correct horse battery staple straße", &strict).contains(&(Severity::Critical, "non_ascii")));
+    }
+
+    #[test]
+    fn normalization() {
+        let nfkd = Policy { min_bits: 0.0, reject_whitespace: false, charset: Charset::NfkdStable };
+        // precomposed é and ﬁ ligature change, decomposed e with combining acute does not
+        assert!(checks("correct horse battery café", &nfkd).contains(&(Severity::Critical, "normalization")));
+        assert!(checks("correct horse battery ﬁsh", &Policy::default()).contains(&(Severity::Warning, "normalization")));
+        assert!(!checks("correct horse battery cafe\u{301}", &nfkd).iter().any(|(_, check)| *check == "normalization"));
+    }
+}
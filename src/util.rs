@@ -44,7 +44,7 @@ pub fn is_alphabetic_whitespace(text: &str) -> bool{
 
 /// Convert binary string to hex
 pub fn binary_to_hex(val: &str) -> Result<String, String> {
-    if val.len() % 8 != 0 {
+    if !val.len().is_multiple_of(8) {
         return Err(String::from("Invalid binary input - length not divisible by 8"));
     }
 
@@ -61,6 +61,7 @@ pub fn binary_to_hex(val: &str) -> Result<String, String> {
 
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
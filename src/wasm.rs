@@ -0,0 +1,100 @@
+//! WebAssembly bindings for browsers and Node, built with `--no-default-features --features wasm`
+//!
+//! Failures throw `Error` with name `MnemonicError` and property `kind` holding `MnemonicError::as_str`, e.g.
+//! `"invalid_checksum"`, or `RANDOMNESS`. Messages are fixed per kind, an invalid word is identified only by property
+//! `position`, its number in the mnemonic starting at 1, since typos of secret words end up in logs. Randomness comes
+//! from `crypto.getRandomValues`.
+
+use crate::seed::SeedDerivationParams;
+use crate::{entropy_to_mnemonic, mnemonic_lookup, mnemonic_to_entropy, normalize_mnemonic, words_with_prefix, MnemonicError};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Web Crypto API, global in browsers and Node 19 and later
+    #[wasm_bindgen(catch, js_namespace = crypto, js_name = getRandomValues)]
+    fn get_random_values(buffer: &mut [u8]) -> Result<(), JsValue>;
+}
+
+/// Kind of thrown error when `crypto.getRandomValues` is not available or failed, other kinds are those of `MnemonicError`
+pub const RANDOMNESS: &str = "randomness";
+
+/// JS error with name `MnemonicError` and given kind
+fn error(kind: &str, message: &str) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name("MnemonicError");
+    // setting property of fresh object cannot fail
+    js_sys::Reflect::set(&error, &JsValue::from_str("kind"), &JsValue::from_str(kind)).expect("kind is set");
+    error.into()
+}
+
+fn mnemonic_error(failure: &MnemonicError) -> JsValue {
+    let message = match failure {
+        MnemonicError::InvalidEntropy(_) => "Entropy has to have 16, 20, 24, 28 or 32 bytes",
+        MnemonicError::InvalidWordCount => "Mnemonic has to have 12, 15, 18, 21 or 24 words",
+        MnemonicError::InvalidWord(_) => "Mnemonic contains word which is not in word list",
+        MnemonicError::InvalidChecksum => "Invalid mnemonic checksum",
+    };
+    error(failure.as_str(), message)
+}
+
+/// Error of given mnemonic with position of its first invalid word
+fn input_error(mnemonic: &str, failure: &MnemonicError) -> JsValue {
+    let error = mnemonic_error(failure);
+    if let MnemonicError::InvalidWord(_) = failure {
+        let normalized = normalize_mnemonic(mnemonic);
+        if let Some(position) = normalized.split(' ').position(|word| mnemonic_lookup(word).is_err()) {
+            js_sys::Reflect::set(&error, &JsValue::from_str("position"), &JsValue::from(position as u32 + 1)).expect("position is set");
+        }
+    }
+    error
+}
+
+/// Generate mnemonic of 12, 15, 18, 21 or 24 words
+#[wasm_bindgen]
+pub fn generate(words: usize) -> Result<String, JsValue> {
+    if !crate::POSSIBLE_LEN.contains(&words) {
+        return Err(mnemonic_error(&MnemonicError::InvalidWordCount));
+    }
+    let mut entropy = [0u8; crate::MAX_ENTROPY];
+    let entropy = &mut entropy[..words * 4 / 3];
+    get_random_values(entropy).map_err(|_| error(RANDOMNESS, "crypto.getRandomValues failed"))?;
+    let mnemonic = entropy_to_mnemonic(entropy).map_err(|error| mnemonic_error(&error));
+    entropy.fill(0);
+    mnemonic
+}
+
+/// Check words and checksum of mnemonic, throws when it is invalid
+#[wasm_bindgen]
+pub fn validate(mnemonic: &str) -> Result<(), JsValue> {
+    mnemonic_to_entropy(mnemonic).map(|_| ()).map_err(|error| input_error(mnemonic, &error))
+}
+
+/// Get words from entropy
+#[wasm_bindgen(js_name = entropyToMnemonic)]
+pub fn entropy_to_mnemonic_js(entropy: &[u8]) -> Result<String, JsValue> {
+    entropy_to_mnemonic(entropy).map_err(|error| mnemonic_error(&error))
+}
+
+/// Get entropy from mnemonic, words may be separated by any whitespace and use any case
+#[wasm_bindgen(js_name = mnemonicToEntropy)]
+pub fn mnemonic_to_entropy_js(mnemonic: &str) -> Result<Vec<u8>, JsValue> {
+    mnemonic_to_entropy(mnemonic).map_err(|error| input_error(mnemonic, &error))
+}
+
+/// Derive 64 bytes of BIP-39 seed from mnemonic, the checksum is verified first
+#[wasm_bindgen(js_name = mnemonicToSeed)]
+pub fn mnemonic_to_seed_js(mnemonic: &str, passphrase: Option<String>) -> Result<Vec<u8>, JsValue> {
+    validate(mnemonic)?;
+    Ok(SeedDerivationParams::default().mnemonic_to_seed(mnemonic, passphrase.as_deref()))
+}
+
+/// Words of the list starting with prefix, case and surrounding whitespace are ignored
+#[wasm_bindgen]
+pub fn autocomplete(prefix: &str) -> Vec<String> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+    words_with_prefix(&prefix).map(String::from).collect()
+}
@@ -0,0 +1,251 @@
+use std::process::{Command, Output, Stdio};
+
+const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+
+fn run(arguments: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mnemonic")).args(arguments).output().unwrap()
+}
+
+fn exit_code(arguments: &[&str]) -> i32 {
+    run(arguments).status.code().unwrap()
+}
+
+#[test]
+fn success() {
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{}\n", SEED));
+    assert_eq!(exit_code(&["verify", MNEMONIC, SEED, "--no-passphrase"]), 0);
+}
+
+#[test]
+fn usage_error() {
+    assert_eq!(exit_code(&["to-seed"]), 2);
+    assert_eq!(exit_code(&["generate", "--words", "13"]), 2);
+    assert_eq!(exit_code(&["--entropy", "00", "--mnemonic", MNEMONIC]), 2);
+    assert_eq!(exit_code(&["--entropy", "00", "--unknown"]), 2);
+    let output = run(&["--mnemonic", MNEMONIC, "--no_passphrase"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("This interface is deprecated"));
+}
+
+#[test]
+fn invalid_input() {
+    assert_eq!(exit_code(&["to-mnemonic", "xyz"]), 3);
+    assert_eq!(exit_code(&["to-mnemonic", "00"]), 3);
+    assert_eq!(exit_code(&["to-entropy", "abandon abandon"]), 3);
+    assert_eq!(exit_code(&["--mnemonic", "abandon 42"]), 3);
+}
+
+#[test]
+fn invalid_word() {
+    let mnemonic = MNEMONIC.replace("about", "abut");
+    assert_eq!(exit_code(&["to-entropy", &mnemonic]), 4);
+    assert_eq!(exit_code(&["--mnemonic", &mnemonic]), 4);
+}
+
+#[test]
+fn checksum_failure() {
+    let mnemonic = MNEMONIC.replace("about", "abandon");
+    assert_eq!(exit_code(&["to-entropy", &mnemonic]), 5);
+    assert_eq!(exit_code(&["to-seed", &mnemonic, "--no-passphrase"]), 5);
+}
+
+#[test]
+fn other_failure() {
+    assert_eq!(exit_code(&["verify", MNEMONIC, SEED, "--passphrase-env", "MNEMONIC_TEST_UNSET_VARIABLE"]), 1);
+}
+
+#[test]
+fn verification_mismatch() {
+    let output = run(&["verify", MNEMONIC, "00", "--no-passphrase"]);
+    assert_eq!(output.status.code(), Some(6));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "NOK\n");
+}
+
+#[test]
+fn io_error() {
+    assert_eq!(exit_code(&["to-entropy", "/nonexistent/mnemonic.txt", "--from-file"]), 7);
+    assert_eq!(exit_code(&["to-entropy", MNEMONIC, "--no-passphrase", "--to-file", "/nonexistent/entropy.txt"]), 7);
+    assert_eq!(exit_code(&["--mnemonic", "/nonexistent/mnemonic.txt", "--from_file"]), 7);
+}
+
+#[test]
+fn json_error() {
+    let output = run(&["to-entropy", &MNEMONIC.replace("about", "abandon"), "--format", "json"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(output.stdout.is_empty());
+    let error = String::from_utf8(output.stderr).unwrap();
+    assert!(error.starts_with("{\"error\":{\"code\":\"invalid_checksum\""));
+}
+
+#[test]
+fn quiet_and_verbose() {
+    let output = run(&["verify", MNEMONIC, "00", "--no-passphrase", "--quiet"]);
+    assert_eq!(output.status.code(), Some(6));
+    assert!(output.stdout.is_empty());
+    let output = run(&["to-entropy", MNEMONIC, "--no-passphrase", "--verbose"]);
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Word indices: 0 0 0 0 0 0 0 0 0 0 0 3\n"));
+    assert!(text.contains("Checksum bits: 0011\n"));
+    assert_eq!(exit_code(&["to-entropy", MNEMONIC, "--quiet", "--verbose"]), 2);
+}
+
+#[test]
+fn redacted_output() {
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase", "--redact"]);
+    let seed = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(seed, format!("5{}4\n", "*".repeat(SEED.len() - 2)));
+}
+
+#[test]
+fn secrets_not_in_errors() {
+    let output = run(&["to-entropy", &MNEMONIC.replace("about", "abut")]);
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "Input error: Invalid word at position 12!\n");
+    let output = run(&["to-entropy", "abandon", "about"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("about"));
+}
+
+#[test]
+fn seed_qr() {
+    let output = run(&["qr", MNEMONIC, "--no-passphrase", "--format", "json"]);
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.contains(&format!("\"payload\":\"{}0003\"", "0000".repeat(11))));
+    let output = run(&["qr", MNEMONIC, "--no-passphrase", "--image", "png"]);
+    assert!(output.stdout.starts_with(b"\x89PNG"));
+    let output = run(&["from-qr", &"0".repeat(32), "--no-passphrase"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{}\n", MNEMONIC));
+    assert_eq!(exit_code(&["from-qr", &"0".repeat(48)]), 5);
+}
+
+#[test]
+fn backup_sheet() {
+    let output = run(&["backup", MNEMONIC, "--no-passphrase"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.starts_with(b"%PDF-"));
+    let output = run(&["backup", MNEMONIC, "--no-passphrase", "--sheet", "svg"]);
+    let svg = String::from_utf8(output.stdout).unwrap();
+    assert!(svg.contains("73c5da0a") && svg.contains(">0000000</text>"));
+    assert_eq!(exit_code(&["backup", MNEMONIC, "--no-passphrase", "--redact"]), 2);
+}
+
+#[test]
+fn expected_fingerprint() {
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase", "--format", "json"]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("\"fingerprint\":\"73c5da0a\""));
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase", "--quiet", "--expect-fingerprint", "73C5DA0A"]);
+    assert_eq!(output.status.code(), Some(0));
+    let output = run(&["derive", MNEMONIC, "--addresses", "1", "--no-passphrase", "--expect-fingerprint", "00000000"]);
+    assert_eq!(output.status.code(), Some(6));
+    assert!(output.stdout.is_empty());
+    assert_eq!(exit_code(&["batch", "to-seed", "/nonexistent/records.txt", "--no-passphrase", "--expect-fingerprint", "73c5da0a"]), 2);
+    assert_eq!(exit_code(&["to-seed", MNEMONIC, "--no-passphrase", "--expect-fingerprint", "73c5"]), 2);
+}
+
+#[test]
+fn fingerprint_of_every_operation() {
+    let entropy = "0".repeat(32);
+    let payload = "0".repeat(32);
+    let operations: [&[&str]; 5] = [
+        &["generate"],
+        &["to-mnemonic", &entropy, "--allow-weak"],
+        &["to-entropy", MNEMONIC],
+        &["qr", MNEMONIC],
+        &["from-qr", &payload],
+    ];
+    for operation in operations {
+        let arguments = [operation, &["--no-passphrase", "--format", "json"]].concat();
+        let report = String::from_utf8(run(&arguments).stdout).unwrap();
+        assert!(report.contains("\"fingerprint\":\""), "{:?}", operation);
+        assert!(report.contains("\"fingerprint_passphrase\":\"empty\""), "{:?}", operation);
+        if operation[0] != "generate" {
+            assert!(report.contains("\"fingerprint\":\"73c5da0a\""), "{:?}", operation);
+            assert_eq!(exit_code(&[operation, &["--no-passphrase", "--expect-fingerprint", "73c5da0a"]].concat()), 0, "{:?}", operation);
+            assert_eq!(exit_code(&[operation, &["--no-passphrase", "--expect-fingerprint", "00000000"]].concat()), 6, "{:?}", operation);
+        }
+    }
+    assert_eq!(exit_code(&["generate", "--no-passphrase", "--expect-fingerprint", "00000000"]), 6);
+    let output = run(&["qr", MNEMONIC, "--no-passphrase", "--image", "svg"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Fingerprint: 73c5da0a\n"));
+}
+
+#[test]
+fn conversion_without_terminal() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mnemonic")).args(["generate", "--words", "12"]).stdin(Stdio::null()).output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().split_whitespace().count(), 12);
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("Fingerprint"));
+    let output = Command::new(env!("CARGO_BIN_EXE_mnemonic")).args(["to-entropy", MNEMONIC, "--format", "json"]).stdin(Stdio::null()).output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("fingerprint"));
+}
+
+#[test]
+fn weak_entropy() {
+    let zeros = "0".repeat(32);
+    let output = run(&["to-mnemonic", &zeros]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--allow-weak"));
+    let output = run(&["to-mnemonic", &zeros, "--no-passphrase", "--allow-weak"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{}\n", MNEMONIC));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("(critical)"));
+    assert_eq!(exit_code(&["to-mnemonic", "c55257c360c07c72029aebc1b53c05ed", "--no-passphrase"]), 0);
+}
+
+#[test]
+fn passphrase_policy() {
+    let run_with = |passphrase: &str, arguments: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_mnemonic")).args(["to-seed", MNEMONIC, "--passphrase-env", "PASSPHRASE"]).args(arguments)
+            .env("PASSPHRASE", passphrase).output().unwrap()
+    };
+    let output = run_with("TREZOR ", &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("weak") && stderr.contains("whitespace"));
+    let output = run_with("TREZOR", &["--min-passphrase-bits", "40"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("does not satisfy policy"));
+    assert_eq!(run_with("correct horse battery staple 42 ", &["--reject-passphrase-whitespace"]).status.code(), Some(1));
+    assert_eq!(run_with("correct horse battery café", &["--passphrase-charset", "nfkd"]).status.code(), Some(1));
+    assert_eq!(run_with("correct horse battery cafe", &["--passphrase-charset", "ascii", "--min-passphrase-bits", "40"]).status.code(), Some(0));
+    assert_eq!(exit_code(&["to-seed", MNEMONIC, "--no-passphrase", "--passphrase-charset", "latin"]), 2);
+}
+
+#[test]
+fn seed_derivation_params() {
+    let electrum = run(&["to-seed", MNEMONIC, "--no-passphrase", "--seed-preset", "electrum"]);
+    assert_eq!(electrum.status.code(), Some(0));
+    let seed = String::from_utf8(electrum.stdout).unwrap();
+    assert_eq!(seed.trim().len(), 128);
+    assert_ne!(seed.trim(), SEED);
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase", "--salt-prefix", "electrum", "--format", "json"]);
+    let json = String::from_utf8(output.stdout).unwrap();
+    assert!(json.contains(&format!("\"seed\":\"{}\"", seed.trim())));
+    assert!(json.contains("\"seed_derivation\":\"salt prefix \\\"electrum\\\", 2048 iterations of hmac-sha512, 64 bytes\""));
+
+    let output = run(&["to-seed", MNEMONIC, "--no-passphrase", "--iterations", "1", "--prf", "hmac-sha256", "--seed-length", "32"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim().len(), 64);
+    assert_eq!(exit_code(&["verify", MNEMONIC, SEED, "--no-passphrase", "--seed-preset", "electrum"]), 6);
+    assert_eq!(exit_code(&["to-seed", MNEMONIC, "--no-passphrase", "--iterations", "0"]), 2);
+    assert_eq!(exit_code(&["to-seed", MNEMONIC, "--no-passphrase", "--seed-length", "128"]), 2);
+}
+
+#[test]
+fn mnemonic_whitespace_and_layouts() {
+    let messy = " Abandon\tabandon\nabandon  abandon abandon\u{3000}abandon abandon abandon abandon abandon abandon ABOUT \r\n";
+    let output = run(&["to-seed", messy, "--no-passphrase"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{}\n", SEED));
+
+    let entropy = "c55257c360c07c72029aebc1b53c05ed";
+    let output = run(&["to-mnemonic", entropy, "--no-passphrase", "--separator", "-"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "shed-naive-valid-screen-amazing-decorate-apology-frost-script-prepare-accident-surge\n");
+    let output = run(&["to-mnemonic", entropy, "--no-passphrase", "--layout", "numbered"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 12);
+    assert!(stdout.starts_with("1. shed\n2. naive\n") && stdout.ends_with("12. surge\n"));
+    assert_eq!(exit_code(&["to-mnemonic", entropy, "--layout", "columns"]), 2);
+}
@@ -0,0 +1,68 @@
+//! Tests of the wasm bindings, run on Node with
+//! `wasm-pack test --node -- --no-default-features --features wasm --test wasm`, or with `cargo test` and the same
+//! options for target `wasm32-unknown-unknown` when `wasm-bindgen-test-runner` is installed
+#![cfg(target_arch = "wasm32")]
+
+use mnemonic::wasm::{autocomplete, entropy_to_mnemonic_js, generate, mnemonic_to_entropy_js, mnemonic_to_seed_js, validate};
+use mnemonic::MnemonicError;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+
+/// Name and kind of thrown error
+fn error_kind(error: JsValue) -> (String, String) {
+    let name = js_sys::Reflect::get(&error, &JsValue::from_str("name")).unwrap().as_string().unwrap();
+    let kind = js_sys::Reflect::get(&error, &JsValue::from_str("kind")).unwrap().as_string().unwrap();
+    (name, kind)
+}
+
+fn to_hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[wasm_bindgen_test]
+fn round_trip() {
+    assert_eq!(entropy_to_mnemonic_js(&[0u8; 16]).unwrap(), MNEMONIC);
+    assert_eq!(mnemonic_to_entropy_js(&MNEMONIC.to_uppercase()).unwrap(), vec![0u8; 16]);
+    assert_eq!(to_hex_string(&mnemonic_to_seed_js(&format!(" {}\n", MNEMONIC), None).unwrap()), SEED);
+    assert_ne!(mnemonic_to_seed_js(MNEMONIC, Some(String::from("TREZOR"))).unwrap(), mnemonic_to_seed_js(MNEMONIC, None).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn generated_mnemonic() {
+    let mnemonic = generate(24).unwrap();
+    assert_eq!(mnemonic.split(' ').count(), 24);
+    assert!(validate(&mnemonic).is_ok());
+    assert_ne!(generate(12).unwrap(), generate(12).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn typed_errors() {
+    let kind = |kind: &str| (String::from("MnemonicError"), String::from(kind));
+    assert_eq!(error_kind(generate(13).unwrap_err()), kind("invalid_word_count"));
+    assert_eq!(error_kind(entropy_to_mnemonic_js(&[0u8; 15]).unwrap_err()), kind("invalid_entropy"));
+    assert_eq!(error_kind(validate("abandon about").unwrap_err()), kind(MnemonicError::InvalidWordCount.as_str()));
+    assert_eq!(error_kind(validate(&MNEMONIC.replace("about", "xyz")).unwrap_err()), kind("invalid_word"));
+    assert_eq!(error_kind(mnemonic_to_seed_js(&MNEMONIC.replace("about", "zoo"), None).unwrap_err()), kind("invalid_checksum"));
+}
+
+#[wasm_bindgen_test]
+fn invalid_word_position() {
+    let error = mnemonic_to_entropy_js(&MNEMONIC.replacen("abandon", "abandom", 2)).unwrap_err();
+    let property = |name: &str| js_sys::Reflect::get(&error, &JsValue::from_str(name)).unwrap();
+    assert_eq!(property("position").as_f64(), Some(1.0));
+    let message = property("message").as_string().unwrap();
+    assert_eq!(message, "Mnemonic contains word which is not in word list");
+    assert!(!message.contains("abandom"));
+    assert!(js_sys::Reflect::get(&validate("abandon about").unwrap_err(), &JsValue::from_str("position")).unwrap().is_undefined());
+}
+
+#[wasm_bindgen_test]
+fn word_autocomplete() {
+    assert_eq!(autocomplete(" ABS"), vec!["absent", "absorb", "abstract", "absurd"]);
+    assert_eq!(autocomplete("zoo"), vec!["zoo"]);
+    assert!(autocomplete("").is_empty());
+    assert!(autocomplete("xyz").is_empty());
+}